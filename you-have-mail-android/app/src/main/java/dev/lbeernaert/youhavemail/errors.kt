@@ -36,6 +36,9 @@ fun serviceExceptionToErrorStr(e: ServiceException, email: String?): String {
                 "Account is offline"
             }
         }
+        is ServiceException.TooManyAttempts -> {
+            return "Too many attempts, please wait and try again"
+        }
         is ServiceException.Config -> {
             return "Configuration error"
         }
@@ -133,6 +133,7 @@ fun ActiveAccount(account: ObserverAccount, index: Int, onClicked: (Int) -> Unit
                 ObserverAccountStatus.OFFLINE -> stringResource(id = R.string.status_offline)
                 ObserverAccountStatus.LOGGED_OUT -> stringResource(id = R.string.status_logged_out)
                 ObserverAccountStatus.ONLINE -> stringResource(id = R.string.status_online)
+                ObserverAccountStatus.ERROR -> stringResource(id = R.string.status_error)
             }
             Text(
                 text = stringResource(id = R.string.status, statusString),
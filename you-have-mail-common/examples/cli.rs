@@ -24,6 +24,8 @@ fn new_backed() -> Arc<dyn Backend> {
         email: "foo".to_string(),
         password: "bar".to_string(),
         totp: None,
+        wait_time: None,
+        ..Default::default()
     }]);
 }
 
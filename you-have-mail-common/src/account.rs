@@ -1,6 +1,7 @@
 use crate::backend::{AuthRefresher, NewEmailReply};
 use proton_api_rs::log::error;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
 /// Represents a user account. While it would have been more idiomatic to have the account
@@ -12,6 +13,11 @@ pub struct Account {
     backend: Arc<dyn crate::backend::Backend>,
     state: AccountState,
     email: String,
+    last_poll: Option<SystemTime>,
+    last_poll_attempt: Option<SystemTime>,
+    created_at: Option<SystemTime>,
+    notify_cooldown: Option<Duration>,
+    last_notified: Option<SystemTime>,
 }
 
 /// Possible states for an account.
@@ -30,11 +36,23 @@ pub enum AccountState {
 pub enum AccountError {
     #[error("Account is not in right state for this operation")]
     InvalidState,
+    #[error("Account requires a TOTP code to complete the login")]
+    TotpRequired,
     #[error("Backend error occurred: {0}")]
     Backend(#[from] crate::backend::BackendError),
 }
 
 impl AccountError {
+    /// Short and stable description of the error category which contains no user data and is
+    /// suitable for error reporting.
+    pub fn telemetry_code(&self) -> &'static str {
+        match self {
+            AccountError::InvalidState => "invalid_state",
+            AccountError::TotpRequired => "totp_required",
+            AccountError::Backend(e) => e.telemetry_code(),
+        }
+    }
+
     pub fn is_offline(&self) -> bool {
         if let AccountError::Backend(e) = self {
             return matches!(e, crate::backend::BackendError::Offline);
@@ -52,12 +70,43 @@ impl AccountError {
 
 pub type AccountResult<T> = Result<T, AccountError>;
 
+/// Longest notify cooldown an account accepts, longer values are clamped to it.
+pub const MAX_NOTIFY_COOLDOWN: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Error returned from [Account::change_backend](fn@Account::change_backend).
+#[derive(Debug, Error)]
+pub enum ChangeBackendError {
+    #[error("Failed to move the session of account '{account}' to backend '{backend}': {error}")]
+    BackendConfig {
+        account: String,
+        backend: String,
+        #[source]
+        error: anyhow::Error,
+    },
+    #[error("{0}")]
+    Account(#[from] AccountError),
+}
+
+/// Outcome of a successful [Account::logout](fn@Account::logout).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LogoutStatus {
+    /// The account is no longer logged in.
+    LoggedOut,
+    /// The backend reported the session as already expired or invalidated.
+    AlreadyExpired,
+}
+
 impl Account {
     pub fn new<T: Into<String>>(backend: Arc<dyn crate::backend::Backend>, email: T) -> Self {
         Self {
             backend,
             state: AccountState::LoggedOut,
             email: email.into(),
+            last_poll: None,
+            last_poll_attempt: None,
+            created_at: Some(SystemTime::now()),
+            notify_cooldown: None,
+            last_notified: None,
         }
     }
 
@@ -70,6 +119,11 @@ impl Account {
             backend,
             state,
             email: email.into(),
+            last_poll: None,
+            last_poll_attempt: None,
+            created_at: Some(SystemTime::now()),
+            notify_cooldown: None,
+            last_notified: None,
         }
     }
 
@@ -80,6 +134,11 @@ impl Account {
             backend: self.backend.clone(),
             email: self.email.clone(),
             state: std::mem::replace(&mut self.state, AccountState::LoggedOut),
+            last_poll: self.last_poll,
+            last_poll_attempt: self.last_poll_attempt,
+            created_at: self.created_at,
+            notify_cooldown: self.notify_cooldown,
+            last_notified: self.last_notified,
         }
     }
 
@@ -103,6 +162,84 @@ impl Account {
         &self.email
     }
 
+    /// Change the account's email.
+    pub(crate) fn set_email(&mut self, email: String) {
+        self.email = email;
+    }
+
+    /// The account owner's display name, if the account is logged in and the backend provides
+    /// one.
+    pub fn display_name(&self) -> Option<&str> {
+        self.get_impl().and_then(|a| a.display_name())
+    }
+
+    /// Time at which the account was first added. Accounts restored from configs which predate
+    /// this information have no creation time.
+    pub fn created_at(&self) -> Option<SystemTime> {
+        self.created_at
+    }
+
+    pub(crate) fn set_created_at(&mut self, created_at: Option<SystemTime>) {
+        self.created_at = created_at;
+    }
+
+    /// Time of the last successful [check](fn@Account::check), if any.
+    pub fn last_poll(&self) -> Option<SystemTime> {
+        self.last_poll
+    }
+
+    pub(crate) fn set_last_poll(&mut self, last_poll: Option<SystemTime>) {
+        self.last_poll = last_poll;
+    }
+
+    /// Time of the last [check](fn@Account::check) attempt, regardless of whether it succeeded.
+    /// The observer also records an attempt for logged out accounts it skips while polling.
+    pub fn last_poll_attempt(&self) -> Option<SystemTime> {
+        self.last_poll_attempt
+    }
+
+    pub(crate) fn set_last_poll_attempt(&mut self, last_poll_attempt: Option<SystemTime>) {
+        self.last_poll_attempt = last_poll_attempt;
+    }
+
+    /// Whether the account has not been successfully checked within `interval` of `now`. Accounts
+    /// which have never been checked are always overdue.
+    pub fn is_poll_overdue(&self, now: SystemTime, interval: Duration) -> bool {
+        match self.last_poll {
+            None => true,
+            Some(t) => t.checked_add(interval).is_some_and(|end| end < now),
+        }
+    }
+
+    /// Minimum time between two new email notifications for this account, if any.
+    pub fn notify_cooldown(&self) -> Option<Duration> {
+        self.notify_cooldown
+    }
+
+    /// Set the minimum time between two new email notifications for this account. New email found
+    /// during the cooldown is notified with the first poll after the cooldown has passed. The
+    /// cooldown is clamped to [MAX_NOTIFY_COOLDOWN].
+    pub fn set_notify_cooldown(&mut self, cooldown: Option<Duration>) {
+        self.notify_cooldown = cooldown.map(|c| c.min(MAX_NOTIFY_COOLDOWN));
+    }
+
+    /// Time of the last new email notification for this account, if any.
+    pub fn last_notified(&self) -> Option<SystemTime> {
+        self.last_notified
+    }
+
+    pub(crate) fn set_last_notified(&mut self, last_notified: Option<SystemTime>) {
+        self.last_notified = last_notified;
+    }
+
+    /// Whether a new email notification at `now` falls within the account's notify cooldown.
+    pub fn is_notify_suppressed(&self, now: SystemTime) -> bool {
+        match (self.notify_cooldown, self.last_notified) {
+            (Some(cooldown), Some(t)) => t.checked_add(cooldown).is_none_or(|end| now < end),
+            _ => false,
+        }
+    }
+
     /// Get the account's backend.
     pub fn backend(&self) -> &dyn crate::backend::Backend {
         self.backend.as_ref()
@@ -110,9 +247,13 @@ impl Account {
 
     /// Run check on the account to see if new emails have arrived.
     pub async fn check(&mut self) -> AccountResult<NewEmailReply> {
+        self.last_poll_attempt = Some(SystemTime::now());
         match &mut self.state {
             AccountState::LoggedIn(a) => match a.check().await {
-                Ok(r) => Ok(r),
+                Ok(r) => {
+                    self.last_poll = Some(SystemTime::now());
+                    Ok(r)
+                }
                 Err(e) => {
                     if matches!(e, crate::backend::BackendError::LoggedOut) {
                         self.state = AccountState::LoggedOut;
@@ -134,18 +275,39 @@ impl Account {
         Ok(())
     }
 
-    /// Logout the current account.
-    pub async fn logout(&mut self) -> AccountResult<()> {
+    /// Login to the account with the given password and, if the account requires 2FA, the
+    /// given `totp` code. If a code is required but none was provided, the account is left
+    /// awaiting totp and `AccountError::TotpRequired` is returned.
+    pub async fn login_with_totp(
+        &mut self,
+        password: &str,
+        totp: Option<&str>,
+    ) -> AccountResult<()> {
+        self.login(password).await?;
+        if !self.is_awaiting_totp() {
+            return Ok(());
+        }
+
+        match totp {
+            Some(totp) => self.submit_totp(totp).await,
+            None => Err(AccountError::TotpRequired),
+        }
+    }
+
+    /// Logout the current account. If the backend reports the session as already logged out,
+    /// the logout is still considered successful since the session is gone either way.
+    pub async fn logout(&mut self) -> AccountResult<LogoutStatus> {
         let old_state = std::mem::replace(&mut self.state, AccountState::LoggedOut);
         match old_state {
-            AccountState::LoggedOut | AccountState::AwaitingTotp(..) => Ok(()),
-            AccountState::LoggedIn(mut account) => {
-                if let Err(e) = account.logout().await {
+            AccountState::LoggedOut | AccountState::AwaitingTotp(..) => Ok(LogoutStatus::LoggedOut),
+            AccountState::LoggedIn(mut account) => match account.logout().await {
+                Ok(()) => Ok(LogoutStatus::LoggedOut),
+                Err(crate::backend::BackendError::LoggedOut) => Ok(LogoutStatus::AlreadyExpired),
+                Err(e) => {
                     let _ = std::mem::replace(&mut self.state, AccountState::LoggedIn(account));
-                    return Err(e.into());
+                    Err(e.into())
                 }
-                Ok(())
-            }
+            },
         }
     }
 
@@ -172,6 +334,51 @@ impl Account {
         }
     }
 
+    /// Override which folders produce new email notifications for this account. See
+    /// [Account::set_notify_folders](fn@crate::backend::Account::set_notify_folders).
+    pub fn set_notify_folders(
+        &mut self,
+        include: Vec<String>,
+        exclude: Vec<String>,
+    ) -> AccountResult<()> {
+        match &mut self.state {
+            AccountState::LoggedIn(a) => {
+                a.set_notify_folders(include, exclude);
+                Ok(())
+            }
+            _ => Err(AccountError::InvalidState),
+        }
+    }
+
+    /// Restart the account's change tracking from the backend's latest state. See
+    /// [Account::resync](fn@crate::backend::Account::resync).
+    pub fn resync(&mut self) -> AccountResult<()> {
+        match &mut self.state {
+            AccountState::LoggedIn(a) => {
+                a.resync();
+                Ok(())
+            }
+            _ => Err(AccountError::InvalidState),
+        }
+    }
+
+    /// Check whether the account's session is still valid without polling for new email. Returns
+    /// false and marks the account as logged out if the session expired.
+    pub async fn verify_credentials(&mut self) -> AccountResult<bool> {
+        match &self.state {
+            AccountState::LoggedIn(a) => match a.verify_credentials().await {
+                Ok(()) => Ok(true),
+                Err(crate::backend::BackendError::LoggedOut) => {
+                    self.state = AccountState::LoggedOut;
+                    Ok(false)
+                }
+                Err(e) => Err(e.into()),
+            },
+            AccountState::LoggedOut => Ok(false),
+            AccountState::AwaitingTotp(_) => Err(AccountError::InvalidState),
+        }
+    }
+
     /// Refresh the authentication token for this account.
     pub async fn refresh(&mut self, refresher: Box<dyn AuthRefresher>) -> AccountResult<()> {
         if !self.is_logged_out() {
@@ -182,6 +389,39 @@ impl Account {
         Ok(())
     }
 
+    /// Move the account to `backend`. A logged in session is restored on the new backend from
+    /// the current session info, so the user does not have to login again. This only works
+    /// for backends which share the same session info, such as the Proton backend variants. If
+    /// the session can not be restored, the account is left unchanged.
+    pub async fn change_backend(
+        &mut self,
+        backend: Arc<dyn crate::backend::Backend>,
+    ) -> Result<(), ChangeBackendError> {
+        let config_error = |error| ChangeBackendError::BackendConfig {
+            account: self.email.clone(),
+            backend: backend.name().to_string(),
+            error,
+        };
+
+        let state = match &self.state {
+            AccountState::LoggedIn(a) => {
+                let value = a.auth_refresher_config().map_err(config_error)?;
+                let refresher = backend
+                    .auth_refresher_from_config(value)
+                    .map_err(config_error)?;
+                Some(refresher.refresh().await.map_err(AccountError::from)?)
+            }
+            AccountState::LoggedOut => None,
+            AccountState::AwaitingTotp(_) => return Err(AccountError::InvalidState.into()),
+        };
+
+        if let Some(state) = state {
+            self.state = state;
+        }
+        self.backend = backend;
+        Ok(())
+    }
+
     pub(crate) fn get_impl(&self) -> Option<&dyn crate::backend::Account> {
         if let AccountState::LoggedIn(a) = &self.state {
             Some(a.as_ref())
@@ -190,3 +430,187 @@ impl Account {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::{BackendError, MockAccount};
+    use crate::{Account, AccountError, AccountState, LogoutStatus, MAX_NOTIFY_COOLDOWN};
+    use proton_api_rs::tokio;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_logout_of_expired_session_succeeds() {
+        let mut mock_account = MockAccount::new();
+        mock_account
+            .expect_logout()
+            .times(1)
+            .returning(|| Err(BackendError::LoggedOut));
+        let mut account = Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        );
+
+        assert_eq!(
+            account.logout().await.unwrap(),
+            LogoutStatus::AlreadyExpired
+        );
+        assert!(account.is_logged_out());
+    }
+
+    #[tokio::test]
+    async fn test_logout_failure_keeps_account_logged_in() {
+        let mut mock_account = MockAccount::new();
+        mock_account
+            .expect_logout()
+            .times(1)
+            .returning(|| Err(BackendError::Offline));
+        let mut account = Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        );
+
+        assert!(account.logout().await.unwrap_err().is_offline());
+        assert!(account.is_logged_in());
+    }
+
+    #[tokio::test]
+    async fn test_login_with_totp() {
+        let backend = crate::backend::null::new_backend(&[crate::backend::null::NullTestAccount {
+            email: "foo".to_string(),
+            password: "foo".to_string(),
+            totp: Some("1234".to_string()),
+            wait_time: None,
+            ..Default::default()
+        }]);
+
+        let mut account = Account::new(backend.clone(), "foo");
+        assert!(matches!(
+            account.login_with_totp("foo", None).await,
+            Err(AccountError::TotpRequired)
+        ));
+        assert!(account.is_awaiting_totp());
+
+        let mut account = Account::new(backend, "foo");
+        account.login_with_totp("foo", Some("1234")).await.unwrap();
+        assert!(account.is_logged_in());
+    }
+
+    #[test]
+    fn test_telemetry_codes() {
+        let cases = [
+            (AccountError::InvalidState, "invalid_state"),
+            (AccountError::TotpRequired, "totp_required"),
+            (BackendError::LoggedOut.into(), "logged_out"),
+            (BackendError::Offline.into(), "offline"),
+            (BackendError::TooManyAttempts.into(), "too_many_attempts"),
+            (
+                BackendError::Request(anyhow::anyhow!("foo@bar.com")).into(),
+                "request",
+            ),
+            (
+                BackendError::Unknown(anyhow::anyhow!("foo@bar.com")).into(),
+                "unknown",
+            ),
+        ];
+
+        for (error, code) in cases {
+            assert_eq!(error.telemetry_code(), code);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_credentials() {
+        let mut mock_account = MockAccount::new();
+        let mut mock_sequence = mockall::Sequence::new();
+        mock_account
+            .expect_verify_credentials()
+            .times(1)
+            .in_sequence(&mut mock_sequence)
+            .returning(|| Ok(()));
+        mock_account
+            .expect_verify_credentials()
+            .times(1)
+            .in_sequence(&mut mock_sequence)
+            .returning(|| Err(BackendError::Offline));
+        mock_account
+            .expect_verify_credentials()
+            .times(1)
+            .in_sequence(&mut mock_sequence)
+            .returning(|| Err(BackendError::LoggedOut));
+        let mut account = Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        );
+
+        assert!(account.verify_credentials().await.unwrap());
+        assert!(account.verify_credentials().await.unwrap_err().is_offline());
+        assert!(account.is_logged_in());
+        assert!(!account.verify_credentials().await.unwrap());
+        assert!(account.is_logged_out());
+        assert!(!account.verify_credentials().await.unwrap());
+    }
+
+    #[test]
+    fn test_notify_cooldown() {
+        let mut account = Account::new(crate::backend::null::new_backend(&[]), "foo");
+        let now = std::time::SystemTime::now();
+        assert!(!account.is_notify_suppressed(now));
+
+        account.set_last_notified(Some(now));
+        assert!(!account.is_notify_suppressed(now));
+
+        account.set_notify_cooldown(Some(Duration::from_secs(60)));
+        assert!(account.is_notify_suppressed(now + Duration::from_secs(30)));
+        assert!(!account.is_notify_suppressed(now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_huge_intervals_do_not_overflow() {
+        let mut account = Account::new(crate::backend::null::new_backend(&[]), "foo");
+        let now = std::time::SystemTime::now();
+
+        account.set_notify_cooldown(Some(Duration::from_secs(u64::MAX)));
+        assert_eq!(account.notify_cooldown(), Some(MAX_NOTIFY_COOLDOWN));
+
+        account.set_last_notified(Some(now));
+        account.set_last_poll(Some(now));
+        assert!(account.is_notify_suppressed(now));
+        assert!(!account.is_poll_overdue(now, Duration::MAX));
+    }
+
+    #[test]
+    fn test_backend_error_is_retryable() {
+        let cases = [
+            (BackendError::LoggedOut, false),
+            (BackendError::Offline, true),
+            (BackendError::TooManyAttempts, false),
+            (BackendError::Request(anyhow::anyhow!("foo")), false),
+            (BackendError::Unknown(anyhow::anyhow!("foo")), false),
+        ];
+
+        for (error, retryable) in cases {
+            assert_eq!(error.is_retryable(), retryable, "{error}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_check_records_attempt_only() {
+        let mut mock_account = MockAccount::new();
+        mock_account
+            .expect_check()
+            .times(1)
+            .returning(|| Err(BackendError::Offline));
+        let mut account = Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        );
+
+        assert!(account.check().await.is_err());
+        assert!(account.last_poll_attempt().is_some());
+        assert!(account.last_poll().is_none());
+    }
+}
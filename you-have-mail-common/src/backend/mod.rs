@@ -22,12 +22,40 @@ pub enum BackendError {
     LoggedOut,
     #[error("The user account server's are not reachable")]
     Offline,
+    #[error("Too many attempts, please wait before trying again")]
+    TooManyAttempts,
     #[error("{0}")]
     Request(#[source] anyhow::Error),
     #[error("An unknown error occurred: {0}")]
     Unknown(#[source] anyhow::Error),
 }
 
+impl BackendError {
+    /// Short and stable description of the error category which contains no user data and is
+    /// suitable for error reporting.
+    pub fn telemetry_code(&self) -> &'static str {
+        match self {
+            BackendError::LoggedOut => "logged_out",
+            BackendError::Offline => "offline",
+            BackendError::TooManyAttempts => "too_many_attempts",
+            BackendError::Request(_) => "request",
+            BackendError::Unknown(_) => "unknown",
+        }
+    }
+
+    /// Whether the error is transient and the operation can be retried soon, e.g: the servers
+    /// are not reachable. Errors which require user action or a longer wait return false.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            BackendError::Offline => true,
+            BackendError::LoggedOut
+            | BackendError::TooManyAttempts
+            | BackendError::Request(_)
+            | BackendError::Unknown(_) => false,
+        }
+    }
+}
+
 pub type BackendResult<T> = Result<T, BackendError>;
 
 /// Reply for new email queries.
@@ -68,6 +96,29 @@ pub trait Account: Send + Sync + Debug {
 
     /// Load the necessary information to refresh the user's account access credentials.
     fn auth_refresher_config(&self) -> Result<serde_json::Value, anyhow::Error>;
+
+    /// Override which folders produce new email notifications. Messages in `include` folders are
+    /// reported in addition to the backend's defaults, messages in `exclude` folders are never
+    /// reported. Exclusions take precedence.
+    ///
+    /// Backends without folder support ignore this.
+    fn set_notify_folders(&mut self, _include: Vec<String>, _exclude: Vec<String>) {}
+
+    /// The account owner's display name, if known.
+    #[allow(clippy::needless_lifetimes)] // Lifetime annotations required for automock.
+    fn display_name<'a>(&'a self) -> Option<&'a str> {
+        None
+    }
+
+    /// Forget the current position in the backend's change feed. The next check starts again from
+    /// the most recent state and does not report messages which arrived before it.
+    ///
+    /// Backends without a change feed ignore this.
+    fn resync(&mut self) {}
+
+    /// Perform a cheap authenticated request to confirm the session is still valid. This does not
+    /// change the account's position in the change feed.
+    async fn verify_credentials(&self) -> BackendResult<()>;
 }
 
 /// Trait for accounts that require 2FA support
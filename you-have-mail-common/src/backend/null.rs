@@ -13,12 +13,16 @@ use std::sync::Arc;
 use std::time::Duration;
 
 #[doc(hidden)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct NullTestAccount {
     pub email: String,
     pub password: String,
     pub totp: Option<String>,
     pub wait_time: Option<Duration>,
+    /// Time each check takes to complete.
+    pub check_wait_time: Option<Duration>,
+    /// Make every n-th check fail with [BackendError::Offline].
+    pub offline_every: Option<usize>,
 }
 
 #[doc(hidden)]
@@ -39,14 +43,28 @@ struct NullBacked {
 struct NullAccount {
     email: String,
     wait_time: Option<Duration>,
+    check_wait_time: Option<Duration>,
+    offline_every: Option<usize>,
+    check_count: usize,
+}
+
+impl NullAccount {
+    fn new(email: String, account: Option<&NullTestAccount>) -> Self {
+        Self {
+            email,
+            wait_time: account.and_then(|a| a.wait_time),
+            check_wait_time: account.and_then(|a| a.check_wait_time),
+            offline_every: account.and_then(|a| a.offline_every),
+            check_count: 0,
+        }
+    }
 }
 
 #[doc(hidden)]
 #[derive(Debug)]
 struct NullAwaitTotp {
-    email: String,
+    account: NullTestAccount,
     totp: String,
-    wait_time: Option<Duration>,
 }
 
 #[doc(hidden)]
@@ -81,15 +99,14 @@ impl Backend for NullBacked {
 
             return if let Some(totp) = &account.totp {
                 Ok(AccountState::AwaitingTotp(Box::new(NullAwaitTotp {
-                    email: email.to_string(),
+                    account: account.clone(),
                     totp: totp.clone(),
-                    wait_time: account.wait_time,
                 })))
             } else {
-                Ok(AccountState::LoggedIn(Box::new(NullAccount {
-                    email: email.to_string(),
-                    wait_time: account.wait_time,
-                })))
+                Ok(AccountState::LoggedIn(Box::new(NullAccount::new(
+                    email.to_string(),
+                    Some(account),
+                ))))
             };
         }
 
@@ -107,10 +124,9 @@ impl Backend for NullBacked {
 #[async_trait]
 impl AuthRefresher for NullAuthRefresher {
     async fn refresh(self: Box<Self>) -> Result<AccountState, BackendError> {
-        Ok(AccountState::LoggedIn(Box::new(NullAccount {
-            email: self.email,
-            wait_time: None,
-        })))
+        Ok(AccountState::LoggedIn(Box::new(NullAccount::new(
+            self.email, None,
+        ))))
     }
 }
 
@@ -122,6 +138,17 @@ struct NullAuthRefresherInfo {
 #[async_trait]
 impl Account for NullAccount {
     async fn check(&mut self) -> BackendResult<NewEmailReply> {
+        if let Some(d) = self.check_wait_time {
+            tokio::time::sleep(d).await;
+        }
+
+        self.check_count += 1;
+        if let Some(n) = self.offline_every {
+            if self.check_count.is_multiple_of(n) {
+                return Err(BackendError::Offline);
+            }
+        }
+
         Ok(NewEmailReply { count: 1 })
     }
 
@@ -138,6 +165,10 @@ impl Account for NullAccount {
         })
         .map_err(|e| anyhow!(e))
     }
+
+    async fn verify_credentials(&self) -> BackendResult<()> {
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -146,7 +177,7 @@ impl AwaitTotp for NullAwaitTotp {
         self: Box<NullAwaitTotp>,
         totp: &str,
     ) -> Result<Box<dyn Account>, (Box<dyn AwaitTotp>, BackendError)> {
-        if let Some(d) = self.wait_time {
+        if let Some(d) = self.account.wait_time {
             tokio::time::sleep(d).await;
         }
 
@@ -154,9 +185,39 @@ impl AwaitTotp for NullAwaitTotp {
             return Err((self, BackendError::Request(anyhow!("Invalid totp"))));
         }
 
-        Ok(Box::new(NullAccount {
-            email: self.email,
-            wait_time: self.wait_time,
-        }))
+        Ok(Box::new(NullAccount::new(
+            self.account.email.clone(),
+            Some(&self.account),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::null::{new_backend, NullTestAccount};
+    use crate::Account;
+    use proton_api_rs::tokio;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_null_backend_check_latency_and_offline() {
+        let backend = new_backend(&[NullTestAccount {
+            email: "foo".to_string(),
+            password: "foo".to_string(),
+            check_wait_time: Some(Duration::from_millis(20)),
+            offline_every: Some(2),
+            ..Default::default()
+        }]);
+
+        let mut account = Account::new(backend, "foo");
+        account.login("foo").await.unwrap();
+
+        let start = std::time::Instant::now();
+        account.check().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert!(account.check().await.unwrap_err().is_offline());
+        account.check().await.unwrap();
+        assert!(account.check().await.unwrap_err().is_offline());
+        assert!(account.is_logged_in());
     }
 }
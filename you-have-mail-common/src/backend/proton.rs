@@ -6,67 +6,309 @@ use crate::backend::{
 use crate::AccountState;
 use anyhow::{anyhow, Error};
 use async_trait::async_trait;
-use proton_api_rs::domain::{EventId, ExposeSecret, LabelID, MessageAction, MoreEvents, UserUid};
+use proton_api_rs::domain::{
+    Event, EventId, ExposeSecret, LabelID, MessageAction, MessageId, MoreEvents, UserUid,
+};
+use proton_api_rs::log::error;
 use proton_api_rs::{
     Client, ClientBuilder, ClientBuilderError, ClientLoginState, HttpClientError, RequestError,
     TOTPClient,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Debug;
 use std::sync::Arc;
 
 /// Create a proton mail backend.
 pub fn new_backend(app_version: &str) -> Arc<dyn Backend> {
     Arc::new(ProtonBackend {
-        builder: ClientBuilder::new().app_version(app_version),
+        name: PROTON_BACKEND_NAME,
+        description: "For Proton accounts (mail.proton.com)",
+        builder: new_client_builder(app_version),
+    })
+}
+
+/// Create a proton mail backend which identifies itself with the `Other` app version. Some
+/// accounts can log in with this version without having to solve a captcha.
+pub fn new_backend_version_other() -> Arc<dyn Backend> {
+    Arc::new(ProtonBackend {
+        name: PROTON_OTHER_BACKEND_NAME,
+        description: "For Proton accounts (mail.proton.com) using the 'Other' app version",
+        builder: new_client_builder("Other"),
     })
 }
 
+fn new_client_builder(app_version: &str) -> ClientBuilder {
+    ClientBuilder::new()
+        .app_version(app_version)
+        .user_agent(&user_agent(
+            "YouHaveMail",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+        ))
+}
+
+/// Compose a user agent in the `<app>/<version> (<platform>)` format.
+fn user_agent(app: &str, version: &str, platform: &str) -> String {
+    format!("{app}/{version} ({platform})")
+}
+
 #[derive(Debug)]
 struct ProtonBackend {
+    name: &'static str,
+    description: &'static str,
     builder: ClientBuilder,
 }
 
+/// Proton API error code for an invalid or expired refresh token.
+const API_CODE_INVALID_REFRESH_TOKEN: u32 = 10013;
+
 const PROTON_BACKEND_NAME: &str = "Proton Mail";
+const PROTON_OTHER_BACKEND_NAME: &str = "Proton Mail V-Other";
 
+/// The account's email is owned by [crate::Account] and is not stored here, so renaming an account
+/// can not leave a stale email in the stored session info.
 #[derive(Debug)]
 struct ProtonAccount {
-    email: String,
     client: Option<Client>,
+    display_name: Option<String>,
+    notify_folders: NotifyFolders,
+    event_state: EventState,
+}
+
+/// User overrides for the folders which produce new email notifications. By default only
+/// messages delivered to the inbox are reported.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct NotifyFolders {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+/// [`NotifyFolders`] converted to label ids so they can be matched against message labels.
+#[derive(Debug, Default)]
+struct FolderFilter {
+    include: Vec<LabelID>,
+    exclude: Vec<LabelID>,
+}
+
+/// Proton system labels which aggregate messages from other folders (All Drafts, All Sent,
+/// All Mail, Outbox, Scheduled, Almost All Mail and Snoozed). Including them would report
+/// messages regardless of the folder they were delivered to.
+const AGGREGATE_SYSTEM_LABEL_IDS: &[&str] = &["1", "2", "5", "9", "12", "15", "16"];
+
+impl FolderFilter {
+    fn new(folders: &NotifyFolders) -> Self {
+        fn to_label_ids<'a>(ids: impl Iterator<Item = &'a String>) -> Vec<LabelID> {
+            ids.filter_map(|id| serde_json::from_value(Value::String(id.clone())).ok())
+                .collect()
+        }
+
+        Self {
+            include: to_label_ids(
+                folders
+                    .include
+                    .iter()
+                    .filter(|id| !AGGREGATE_SYSTEM_LABEL_IDS.contains(&id.as_str())),
+            ),
+            exclude: to_label_ids(folders.exclude.iter()),
+        }
+    }
+
+    /// Whether a message with the given `labels` should produce a notification. Excluded folders
+    /// take precedence over the included ones.
+    fn should_notify(&self, labels: &[LabelID]) -> bool {
+        if labels.iter().any(|l| self.exclude.contains(l)) {
+            return false;
+        }
+
+        labels.contains(&LabelID::inbox()) || labels.iter().any(|l| self.include.contains(l))
+    }
+}
+
+/// Maximum number of message ids remembered to avoid notifying about the same message twice.
+const NOTIFIED_MESSAGES_CAPACITY: usize = 200;
+
+/// Bounded set of the most recently notified message ids. Once full, the oldest ids are
+/// evicted first.
+#[derive(Debug, Default)]
+struct NotifiedMessages {
+    order: VecDeque<MessageId>,
+    ids: HashSet<MessageId>,
+}
+
+impl NotifiedMessages {
+    /// Record `id` as notified. Returns false if the id had already been recorded.
+    fn insert(&mut self, id: &MessageId) -> bool {
+        if self.ids.contains(id) {
+            return false;
+        }
+
+        if self.order.len() == NOTIFIED_MESSAGES_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+
+        self.order.push_back(id.clone());
+        self.ids.insert(id.clone());
+        true
+    }
+}
+
+/// Maximum number of event pages processed in a single check. Any remaining events are
+/// processed by the following checks.
+const MAX_EVENT_PAGES_PER_CHECK: usize = 50;
+
+/// Event loop state for an account.
+#[derive(Debug, Default)]
+struct EventState {
     last_event_id: Option<EventId>,
+    notified: NotifiedMessages,
+    folders: FolderFilter,
+}
+
+/// Source of the API events, abstracted so that the event loop can be tested without a server.
+#[async_trait]
+trait EventSource: Send + Sync {
+    async fn latest_event_id(&self) -> Result<EventId, RequestError>;
+    async fn event(&self, event_id: &EventId) -> Result<Event, RequestError>;
+}
+
+#[async_trait]
+impl EventSource for Client {
+    async fn latest_event_id(&self) -> Result<EventId, RequestError> {
+        self.get_latest_event_id().await
+    }
+
+    async fn event(&self, event_id: &EventId) -> Result<Event, RequestError> {
+        self.get_event(event_id).await
+    }
+}
+
+impl EventState {
+    /// Forget the last seen event so the next check restarts from the latest event.
+    fn reset(&mut self) {
+        self.last_event_id = None;
+    }
+
+    /// Process all the events since the last check and count the new messages in the inbox.
+    /// If fetching an event fails after some new messages have already been counted, those are
+    /// still reported and the next check resumes from the last processed event. The event
+    /// cursor and the notified ids have already moved past them, so dropping the count would
+    /// lose those messages for good. Only an expired session is always returned, since the
+    /// account has to login again anyway.
+    async fn check(&mut self, source: &dyn EventSource) -> BackendResult<NewEmailReply> {
+        if self.last_event_id.is_none() {
+            self.last_event_id = Some(source.latest_event_id().await?);
+        }
+
+        let mut result = NewEmailReply { count: 0 };
+
+        if let Some(event_id) = &mut self.last_event_id {
+            let mut has_more = MoreEvents::No;
+            let mut pages = 0;
+            while pages < MAX_EVENT_PAGES_PER_CHECK {
+                let event = match source.event(event_id).await {
+                    Ok(event) => event,
+                    Err(e) => {
+                        let e = BackendError::from(e);
+                        if result.count > 0 && !matches!(e, BackendError::LoggedOut) {
+                            error!("Failed to fetch event, reporting partial result: {e}");
+                            break;
+                        }
+                        return Err(e);
+                    }
+                };
+                if event.event_id != *event_id || has_more == MoreEvents::Yes {
+                    if let Some(message_events) = &event.messages {
+                        for msg_event in message_events {
+                            if msg_event.action == MessageAction::Create
+                                && self.folders.should_notify(&msg_event.message.labels)
+                                && self.notified.insert(&msg_event.id)
+                            {
+                                result.count += 1
+                            }
+                        }
+                    }
+
+                    *event_id = event.event_id;
+                    has_more = event.more;
+                    pages += 1;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        Ok(result)
+    }
 }
 
 #[derive(Debug)]
 struct ProtonAuthRefresher {
     builder: ClientBuilder,
-    email: String,
     uid: String,
     token: String,
+    display_name: Option<String>,
+    notify_folders: NotifyFolders,
 }
 
 #[derive(Deserialize)]
 struct ProtonAuthRefresherInfo {
-    email: String,
     uid: String,
     token: String,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    notify_folders: NotifyFolders,
 }
 
 #[derive(Serialize)]
 struct ProtonAuthRefresherInfoRead<'a> {
-    email: &'a str,
     uid: &'a str,
     token: &'a str,
+    display_name: Option<&'a str>,
+    notify_folders: &'a NotifyFolders,
 }
 
 impl ProtonAccount {
-    fn new(c: Client, email: String) -> Self {
+    fn new(c: Client) -> Self {
         Self {
-            email,
             client: Some(c),
-            last_event_id: None,
+            display_name: None,
+            notify_folders: NotifyFolders::default(),
+            event_state: EventState::default(),
         }
     }
+
+    /// Create a new account after a successful login and retrieve the user's profile. Failing
+    /// to retrieve the profile is not fatal, as it is only informational.
+    async fn new_logged_in(c: Client, email: &str) -> Self {
+        let display_name = match c.get_user().await {
+            Ok(user) => Some(user.display_name).filter(|n| !n.is_empty()),
+            Err(e) => {
+                error!("Failed to retrieve user info for account={email}: {e}");
+                None
+            }
+        };
+
+        Self {
+            display_name,
+            ..Self::new(c)
+        }
+    }
+
+    /// Serialize the information required to restore the session with `uid` and `token`.
+    fn auth_refresher_value(&self, uid: &str, token: &str) -> Result<Value, Error> {
+        let info = ProtonAuthRefresherInfoRead {
+            uid,
+            token,
+            display_name: self.display_name.as_deref(),
+            notify_folders: &self.notify_folders,
+        };
+        serde_json::to_value(&info).map_err(|e| anyhow!(e))
+    }
 }
 
 #[derive(Debug)]
@@ -78,17 +320,17 @@ struct ProtonAwaitTotp {
 #[async_trait]
 impl Backend for ProtonBackend {
     fn name(&self) -> &str {
-        PROTON_BACKEND_NAME
+        self.name
     }
 
     fn description(&self) -> &str {
-        "For Proton accounts (mail.proton.com)"
+        self.description
     }
 
     async fn login(&self, email: &str, password: &str) -> BackendResult<AccountState> {
         match self.builder.clone().login(email, password).await? {
             ClientLoginState::Authenticated(c) => Ok(AccountState::LoggedIn(Box::new(
-                ProtonAccount::new(c, email.to_string()),
+                ProtonAccount::new_logged_in(c, email).await,
             ))),
             ClientLoginState::AwaitingTotp(c) => {
                 Ok(AccountState::AwaitingTotp(Box::new(ProtonAwaitTotp {
@@ -104,9 +346,10 @@ impl Backend for ProtonBackend {
             serde_json::from_value::<ProtonAuthRefresherInfo>(value).map_err(|e| anyhow!(e))?;
         Ok(Box::new(ProtonAuthRefresher {
             builder: self.builder.clone(),
-            email: config.email,
             uid: config.uid,
             token: config.token,
+            display_name: config.display_name,
+            notify_folders: config.notify_folders,
         }))
     }
 }
@@ -114,36 +357,8 @@ impl Backend for ProtonBackend {
 #[async_trait]
 impl Account for ProtonAccount {
     async fn check(&mut self) -> BackendResult<NewEmailReply> {
-        if let Some(client) = &mut self.client {
-            if self.last_event_id.is_none() {
-                let event_id = client.get_latest_event_id().await?;
-                self.last_event_id = Some(event_id);
-            }
-
-            let mut result = NewEmailReply { count: 0 };
-
-            if let Some(event_id) = &mut self.last_event_id {
-                let mut has_more = MoreEvents::No;
-                loop {
-                    let event = client.get_event(event_id).await?;
-                    if event.event_id != *event_id || has_more == MoreEvents::Yes {
-                        if let Some(message_events) = &event.messages {
-                            for msg_event in message_events {
-                                if msg_event.action == MessageAction::Create
-                                    && msg_event.message.labels.contains(&LabelID::inbox())
-                                {
-                                    result.count += 1
-                                }
-                            }
-                        }
-
-                        *event_id = event.event_id;
-                        has_more = event.more;
-                    } else {
-                        return Ok(result);
-                    }
-                }
-            }
+        if let Some(client) = &self.client {
+            return self.event_state.check(client).await;
         }
 
         Err(BackendError::Unknown(anyhow!("Client is no longer active")))
@@ -163,13 +378,32 @@ impl Account for ProtonAccount {
         let Some(client) = &self.client else {
             return Err(anyhow!("invalid state"));
         };
-        let info = ProtonAuthRefresherInfoRead {
-            email: &self.email,
-            uid: client.user_uid().expose_secret().as_str(),
-            token: client.user_refresh_token().expose_secret().as_str(),
+        self.auth_refresher_value(
+            client.user_uid().expose_secret().as_str(),
+            client.user_refresh_token().expose_secret().as_str(),
+        )
+    }
+
+    fn set_notify_folders(&mut self, include: Vec<String>, exclude: Vec<String>) {
+        self.notify_folders = NotifyFolders { include, exclude };
+        self.event_state.folders = FolderFilter::new(&self.notify_folders);
+    }
+
+    fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+
+    fn resync(&mut self) {
+        self.event_state.reset();
+    }
+
+    async fn verify_credentials(&self) -> BackendResult<()> {
+        let Some(client) = &self.client else {
+            return Err(BackendError::Unknown(anyhow!("Client is no longer active")));
         };
-        let value = serde_json::to_value(&info).map_err(|e| anyhow!(e))?;
-        Ok(value)
+
+        client.get_latest_event_id().await?;
+        Ok(())
     }
 }
 
@@ -180,7 +414,7 @@ impl AwaitTotp for ProtonAwaitTotp {
         totp: &str,
     ) -> Result<Box<dyn Account>, (Box<dyn AwaitTotp>, BackendError)> {
         match self.client.submit_totp(totp).await {
-            Ok(c) => Ok(Box::new(ProtonAccount::new(c, self.email))),
+            Ok(c) => Ok(Box::new(ProtonAccount::new_logged_in(c, &self.email).await)),
             Err((c, e)) => {
                 self.client = c;
                 Err((self, e.into()))
@@ -196,9 +430,10 @@ impl AuthRefresher for ProtonAuthRefresher {
             .builder
             .with_token(&UserUid::from(self.uid), &self.token)
             .await?;
-        Ok(AccountState::LoggedIn(Box::new(ProtonAccount::new(
-            client, self.email,
-        ))))
+        let mut account = ProtonAccount::new(client);
+        account.display_name = self.display_name;
+        account.set_notify_folders(self.notify_folders.include, self.notify_folders.exclude);
+        Ok(AccountState::LoggedIn(Box::new(account)))
     }
 }
 
@@ -222,9 +457,12 @@ impl From<RequestError> for BackendError {
                 _ => BackendError::Unknown(anyhow!(e)),
             },
             RequestError::API(e) => {
-                if e.http_code == 401 {
+                if e.http_code == 401 || e.api_code == API_CODE_INVALID_REFRESH_TOKEN {
                     return BackendError::LoggedOut;
                 }
+                if e.http_code == 429 {
+                    return BackendError::TooManyAttempts;
+                }
                 BackendError::Request(anyhow!(e))
             }
             RequestError::JSON(e) => BackendError::Request(anyhow!(e)),
@@ -232,3 +470,328 @@ impl From<RequestError> for BackendError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::proton::{
+        user_agent, EventSource, EventState, FolderFilter, NotifyFolders, ProtonAccount,
+        ProtonAuthRefresherInfo, MAX_EVENT_PAGES_PER_CHECK,
+    };
+    use crate::backend::BackendError;
+    use async_trait::async_trait;
+    use proton_api_rs::domain::{Event, EventId};
+    use proton_api_rs::{tokio, APIError, HttpClientError, RequestError};
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// Replays a fixed list of events, then keeps reporting the last event id as unchanged or
+    /// fails with `error_when_done` if set.
+    struct ReplayEventSource {
+        events: Mutex<VecDeque<Event>>,
+        error_when_done: Option<fn() -> RequestError>,
+    }
+
+    impl ReplayEventSource {
+        fn new(events: Vec<serde_json::Value>) -> Self {
+            Self {
+                events: Mutex::new(
+                    events
+                        .into_iter()
+                        .map(|v| serde_json::from_value(v).unwrap())
+                        .collect(),
+                ),
+                error_when_done: None,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl EventSource for ReplayEventSource {
+        async fn latest_event_id(&self) -> Result<EventId, RequestError> {
+            Ok(EventId("latest".to_string()))
+        }
+
+        async fn event(&self, event_id: &EventId) -> Result<Event, RequestError> {
+            if let Some(event) = self.events.lock().unwrap().pop_front() {
+                return Ok(event);
+            }
+
+            if let Some(error) = self.error_when_done {
+                return Err(error());
+            }
+
+            Ok(serde_json::from_value(new_event(&event_id.0, vec![])).unwrap())
+        }
+    }
+
+    fn offline_error() -> RequestError {
+        RequestError::HttpClient(HttpClientError::Connection)
+    }
+
+    fn new_event(event_id: &str, messages: Vec<serde_json::Value>) -> serde_json::Value {
+        serde_json::json!({"EventID": event_id, "More": 0, "Messages": messages})
+    }
+
+    fn created_message(id: &str, labels: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "ID": id,
+            "Action": 1,
+            "Message": {"ID": id, "LabelIDs": labels},
+        })
+    }
+
+    #[tokio::test]
+    async fn same_message_in_consecutive_checks_is_only_counted_once() {
+        let mut state = EventState {
+            last_event_id: Some(EventId("0".to_string())),
+            ..Default::default()
+        };
+
+        let source =
+            ReplayEventSource::new(vec![new_event("1", vec![created_message("foo", &["0"])])]);
+        let reply = state.check(&source).await.unwrap();
+        assert_eq!(reply.count, 1);
+
+        let source = ReplayEventSource::new(vec![new_event(
+            "2",
+            vec![
+                created_message("foo", &["0"]),
+                created_message("bar", &["0"]),
+            ],
+        )]);
+        let reply = state.check(&source).await.unwrap();
+        assert_eq!(reply.count, 1);
+        assert_eq!(state.last_event_id, Some(EventId("2".to_string())));
+    }
+
+    #[tokio::test]
+    async fn messages_before_a_failed_event_are_still_reported() {
+        let mut state = EventState {
+            last_event_id: Some(EventId("0".to_string())),
+            ..Default::default()
+        };
+
+        let mut first_page = new_event("1", vec![created_message("foo", &["0"])]);
+        first_page["More"] = serde_json::json!(1);
+        let mut source = ReplayEventSource::new(vec![first_page]);
+        source.error_when_done = Some(offline_error);
+        let reply = state.check(&source).await.unwrap();
+        assert_eq!(reply.count, 1);
+        assert_eq!(state.last_event_id, Some(EventId("1".to_string())));
+
+        let mut source = ReplayEventSource::new(vec![]);
+        source.error_when_done = Some(offline_error);
+        assert!(matches!(
+            state.check(&source).await,
+            Err(BackendError::Offline)
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_error_after_counted_messages_keeps_the_count() {
+        let mut state = EventState {
+            last_event_id: Some(EventId("0".to_string())),
+            ..Default::default()
+        };
+
+        let mut first_page = new_event("1", vec![created_message("foo", &["0"])]);
+        first_page["More"] = serde_json::json!(1);
+        let mut source = ReplayEventSource::new(vec![first_page]);
+        source.error_when_done = Some(|| {
+            RequestError::API(APIError {
+                http_code: 503,
+                api_code: 0,
+                message: None,
+            })
+        });
+        let reply = state.check(&source).await.unwrap();
+        assert_eq!(reply.count, 1);
+        assert_eq!(state.last_event_id, Some(EventId("1".to_string())));
+
+        let mut source = ReplayEventSource::new(vec![]);
+        source.error_when_done = Some(|| {
+            RequestError::API(APIError {
+                http_code: 503,
+                api_code: 0,
+                message: None,
+            })
+        });
+        assert!(matches!(
+            state.check(&source).await,
+            Err(BackendError::Request(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn session_expiry_after_counted_messages_is_reported() {
+        let mut state = EventState {
+            last_event_id: Some(EventId("0".to_string())),
+            ..Default::default()
+        };
+
+        let mut first_page = new_event("1", vec![created_message("foo", &["0"])]);
+        first_page["More"] = serde_json::json!(1);
+        let mut source = ReplayEventSource::new(vec![first_page]);
+        source.error_when_done = Some(|| {
+            RequestError::API(APIError {
+                http_code: 401,
+                api_code: 0,
+                message: None,
+            })
+        });
+        assert!(matches!(
+            state.check(&source).await,
+            Err(BackendError::LoggedOut)
+        ));
+    }
+
+    #[tokio::test]
+    async fn long_event_backlog_is_processed_over_multiple_checks() {
+        let mut state = EventState {
+            last_event_id: Some(EventId("0".to_string())),
+            ..Default::default()
+        };
+
+        let total_pages = MAX_EVENT_PAGES_PER_CHECK + 10;
+        let pages = (1..=total_pages)
+            .map(|i| {
+                let mut page = new_event(
+                    &i.to_string(),
+                    vec![created_message(&i.to_string(), &["0"])],
+                );
+                page["More"] = serde_json::json!(1);
+                page
+            })
+            .collect();
+        let source = ReplayEventSource::new(pages);
+
+        let reply = state.check(&source).await.unwrap();
+        assert_eq!(reply.count, MAX_EVENT_PAGES_PER_CHECK);
+        assert_eq!(
+            state.last_event_id,
+            Some(EventId(MAX_EVENT_PAGES_PER_CHECK.to_string()))
+        );
+
+        let reply = state.check(&source).await.unwrap();
+        assert_eq!(reply.count, 10);
+        assert_eq!(state.last_event_id, Some(EventId(total_pages.to_string())));
+    }
+
+    #[tokio::test]
+    async fn reset_restarts_from_latest_event() {
+        let mut state = EventState {
+            last_event_id: Some(EventId("0".to_string())),
+            ..Default::default()
+        };
+
+        state.reset();
+        let reply = state.check(&ReplayEventSource::new(vec![])).await.unwrap();
+        assert_eq!(reply.count, 0);
+        assert_eq!(state.last_event_id, Some(EventId("latest".to_string())));
+    }
+
+    #[tokio::test]
+    async fn notify_folder_overrides_are_honored() {
+        let mut state = EventState {
+            last_event_id: Some(EventId("0".to_string())),
+            folders: FolderFilter::new(&NotifyFolders {
+                include: vec!["custom".to_string()],
+                exclude: vec!["0".to_string()],
+            }),
+            ..Default::default()
+        };
+
+        let source = ReplayEventSource::new(vec![new_event(
+            "1",
+            vec![
+                created_message("inbox", &["0"]),
+                created_message("custom", &["custom"]),
+                created_message("other", &["other"]),
+            ],
+        )]);
+        let reply = state.check(&source).await.unwrap();
+        assert_eq!(reply.count, 1);
+    }
+
+    #[test]
+    fn aggregate_system_labels_are_not_included() {
+        let filter = FolderFilter::new(&NotifyFolders {
+            include: vec!["5".to_string(), "custom".to_string()],
+            exclude: vec![],
+        });
+
+        assert_eq!(filter.include.len(), 1);
+        let all_mail = serde_json::from_value(serde_json::json!("5")).unwrap();
+        assert!(!filter.should_notify(&[all_mail]));
+    }
+
+    #[test]
+    fn user_agent_includes_app_version_and_platform() {
+        assert_eq!(
+            user_agent("YouHaveMail", "0.1.0", "android"),
+            "YouHaveMail/0.1.0 (android)"
+        );
+    }
+
+    #[test]
+    fn auth_refresher_info_from_older_versions_loads() {
+        let info = serde_json::from_value::<ProtonAuthRefresherInfo>(serde_json::json!({
+            "email": "foo@bar.com",
+            "uid": "uid",
+            "token": "token",
+        }))
+        .unwrap();
+
+        assert_eq!(info.uid, "uid");
+        assert_eq!(info.token, "token");
+        assert!(info.display_name.is_none());
+        assert!(info.notify_folders.include.is_empty());
+        assert!(info.notify_folders.exclude.is_empty());
+    }
+
+    #[test]
+    fn auth_refresher_value_round_trips() {
+        let account = ProtonAccount {
+            client: None,
+            display_name: Some("Foo".to_string()),
+            notify_folders: NotifyFolders {
+                include: vec!["10".to_string()],
+                exclude: vec!["20".to_string()],
+            },
+            event_state: EventState::default(),
+        };
+
+        let value = account.auth_refresher_value("uid", "token").unwrap();
+        assert!(value.get("email").is_none());
+        let info = serde_json::from_value::<ProtonAuthRefresherInfo>(value).unwrap();
+
+        assert_eq!(info.uid, "uid");
+        assert_eq!(info.token, "token");
+        assert_eq!(info.display_name.as_deref(), Some("Foo"));
+        assert_eq!(info.notify_folders.include, ["10"]);
+        assert_eq!(info.notify_folders.exclude, ["20"]);
+    }
+
+    #[test]
+    fn invalid_refresh_token_maps_to_logged_out() {
+        let err = RequestError::API(APIError {
+            http_code: 400,
+            api_code: 10013,
+            message: None,
+        });
+        assert!(matches!(BackendError::from(err), BackendError::LoggedOut));
+    }
+
+    #[test]
+    fn rate_limited_requests_map_to_too_many_attempts() {
+        let err = RequestError::API(APIError {
+            http_code: 429,
+            api_code: 0,
+            message: None,
+        });
+        assert!(matches!(
+            BackendError::from(err),
+            BackendError::TooManyAttempts
+        ));
+    }
+}
@@ -4,6 +4,7 @@ use anyhow::anyhow;
 use proton_api_rs::tokio;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 /// Config stores a You Have Mail application state with all the active user accounts
@@ -27,6 +28,17 @@ pub enum ConfigLoadError {
     JSON(#[source] anyhow::Error),
 }
 
+impl ConfigLoadError {
+    /// The email of the account which failed to load, if the error is specific to one account.
+    pub fn account(&self) -> Option<&str> {
+        match self {
+            ConfigLoadError::BackendNotFound { account, .. }
+            | ConfigLoadError::BackendConfig { account, .. } => Some(account),
+            ConfigLoadError::JSON(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ConfigGenError {
     #[error("An error occurred while serializing auth info account '{account}'")]
@@ -40,55 +52,80 @@ pub enum ConfigGenError {
 
 pub type ConfigAccount = (Account, Option<Box<dyn AuthRefresher>>);
 
+/// Config entry of an account which failed to load. It can be passed back to
+/// [Config::store_with_unloaded](fn@Config::store_with_unloaded) so the account is not lost
+/// when the config is written again.
+pub struct UnloadedAccount(ConfigJSONAccount);
+
+impl UnloadedAccount {
+    pub fn email(&self) -> &str {
+        &self.0.email
+    }
+
+    pub fn backend(&self) -> &str {
+        &self.0.backend
+    }
+}
+
+impl std::fmt::Debug for UnloadedAccount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnloadedAccount")
+            .field("email", &self.0.email)
+            .field("backend", &self.0.backend)
+            .finish()
+    }
+}
+
+pub type ConfigLoadFailure = (UnloadedAccount, ConfigLoadError);
+
 impl Config {
     pub fn load(
         backends: &[Arc<dyn Backend>],
         data: &[u8],
     ) -> Result<Vec<ConfigAccount>, ConfigLoadError> {
+        let (accounts, errors) = Self::load_partial(backends, data)?;
+        if let Some((_, e)) = errors.into_iter().next() {
+            return Err(e);
+        }
+
+        Ok(accounts)
+    }
+
+    /// Same as [Config::load](fn@Config::load), but accounts which fail to load are reported in
+    /// the second list instead of aborting the whole load. Only failing to parse the config
+    /// itself is returned as an error.
+    pub fn load_partial(
+        backends: &[Arc<dyn Backend>],
+        data: &[u8],
+    ) -> Result<(Vec<ConfigAccount>, Vec<ConfigLoadFailure>), ConfigLoadError> {
         let config = serde_json::from_slice::<ConfigJSON>(data)
             .map_err(|e| ConfigLoadError::JSON(anyhow!(e)))?;
 
         let mut result = Vec::with_capacity(config.accounts.len());
-
-        fn find_backend_with_tag(
-            backends: &[Arc<dyn Backend>],
-            tag: &str,
-        ) -> Option<Arc<dyn Backend>> {
-            for b in backends {
-                if b.name() == tag {
-                    return Some(b.clone());
-                }
-            }
-            None
-        }
+        let mut errors = Vec::new();
 
         for account in config.accounts {
-            let Some(b) = find_backend_with_tag(backends, &account.backend) else {
-                return Err(ConfigLoadError::BackendNotFound {account:account.email,backend:account.backend });
-            };
-
-            let refresher = if let Some(value) = account.value {
-                Some(b.auth_refresher_from_config(value).map_err(|e| {
-                    ConfigLoadError::BackendConfig {
-                        account: account.email.clone(),
-                        backend: account.backend,
-                        error: e,
-                    }
-                })?)
-            } else {
-                None
-            };
-
-            let account = Account::new(b, account.email);
-
-            result.push((account, refresher));
+            match load_account(backends, account.clone()) {
+                Ok(a) => result.push(a),
+                Err(e) => errors.push((UnloadedAccount(account), e)),
+            }
         }
 
-        Ok(result)
+        Ok((result, errors))
     }
 
     pub fn store<'a>(
         accounts: impl Iterator<Item = &'a Account>,
+    ) -> Result<String, ConfigGenError> {
+        Self::store_with_unloaded(accounts, std::iter::empty())
+    }
+
+    /// Same as [Config::store](fn@Config::store), but also writes back the unchanged entries of
+    /// accounts which failed to load. Entries for an email which is also in `accounts` are
+    /// skipped.
+    pub fn store_with_unloaded<'a>(
+        accounts: impl Iterator<Item = &'a Account>,
+        unloaded: impl Iterator<Item = &'a UnloadedAccount>,
     ) -> Result<String, ConfigGenError> {
         let mut json_accounts = Vec::<ConfigJSONAccount>::new();
 
@@ -109,9 +146,20 @@ impl Config {
                 email: account.email().to_string(),
                 backend: account.backend().name().to_string(),
                 value,
+                last_poll: account.last_poll().and_then(to_unix_secs),
+                last_poll_attempt: account.last_poll_attempt().and_then(to_unix_secs),
+                created_at: account.created_at().and_then(to_unix_secs),
+                notify_cooldown: account.notify_cooldown().map(|d| d.as_secs()),
             })
         }
 
+        for account in unloaded {
+            if json_accounts.iter().any(|a| a.email == account.0.email) {
+                continue;
+            }
+            json_accounts.push(account.0.clone());
+        }
+
         let config_json = ConfigJSON {
             accounts: json_accounts,
         };
@@ -120,11 +168,78 @@ impl Config {
     }
 }
 
-#[derive(Deserialize, Serialize)]
+fn find_backend_with_tag(backends: &[Arc<dyn Backend>], tag: &str) -> Option<Arc<dyn Backend>> {
+    for b in backends {
+        if b.name() == tag {
+            return Some(b.clone());
+        }
+    }
+    None
+}
+
+fn load_account(
+    backends: &[Arc<dyn Backend>],
+    account: ConfigJSONAccount,
+) -> Result<ConfigAccount, ConfigLoadError> {
+    let Some(b) = find_backend_with_tag(backends, &account.backend) else {
+        return Err(ConfigLoadError::BackendNotFound {
+            account: account.email,
+            backend: account.backend,
+        });
+    };
+
+    let refresher = if let Some(value) = account.value {
+        Some(
+            b.auth_refresher_from_config(value)
+                .map_err(|e| ConfigLoadError::BackendConfig {
+                    account: account.email.clone(),
+                    backend: account.backend,
+                    error: e,
+                })?,
+        )
+    } else {
+        None
+    };
+
+    let last_poll = account.last_poll.and_then(from_unix_secs);
+    let last_poll_attempt = account.last_poll_attempt.and_then(from_unix_secs);
+    let created_at = account.created_at.and_then(from_unix_secs);
+    let notify_cooldown = account.notify_cooldown.map(Duration::from_secs);
+    let mut account = Account::new(b, account.email);
+    account.set_created_at(created_at);
+    account.set_notify_cooldown(notify_cooldown);
+    account.set_last_poll(last_poll);
+    account.set_last_poll_attempt(last_poll_attempt);
+
+    Ok((account, refresher))
+}
+
+#[derive(Clone, Deserialize, Serialize)]
 struct ConfigJSONAccount {
     email: String,
     backend: String,
     value: Option<serde_json::Value>,
+    /// Unix timestamp in seconds of the last successful poll.
+    #[serde(default)]
+    last_poll: Option<u64>,
+    /// Unix timestamp in seconds of the last poll attempt.
+    #[serde(default)]
+    last_poll_attempt: Option<u64>,
+    /// Unix timestamp in seconds of when the account was added.
+    #[serde(default)]
+    created_at: Option<u64>,
+    /// Minimum time in seconds between two new email notifications.
+    #[serde(default)]
+    notify_cooldown: Option<u64>,
+}
+
+/// Timestamps which can not be represented are ignored instead of failing the whole load.
+fn from_unix_secs(secs: u64) -> Option<SystemTime> {
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+fn to_unix_secs(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
 }
 
 #[derive(Deserialize, Serialize)]
@@ -140,12 +255,14 @@ async fn test_config_store_and_load() {
             password: "foo".to_string(),
             totp: None,
             wait_time: None,
+            ..Default::default()
         },
         crate::backend::null::NullTestAccount {
             email: "bar".to_string(),
             password: "bar".to_string(),
             totp: None,
             wait_time: None,
+            ..Default::default()
         },
     ]);
 
@@ -166,3 +283,149 @@ async fn test_config_store_and_load() {
     assert!(accounts[0].1.is_some());
     assert!(accounts[1].1.is_none());
 }
+
+#[tokio::test]
+async fn test_config_restores_last_poll() {
+    let null_backed = crate::backend::null::new_backend(&[crate::backend::null::NullTestAccount {
+        email: "foo".to_string(),
+        password: "foo".to_string(),
+        totp: None,
+        wait_time: None,
+        ..Default::default()
+    }]);
+
+    let mut account = Account::new(null_backed.clone(), "foo");
+    account.login("foo").await.unwrap();
+    assert!(account.last_poll().is_none());
+    account.check().await.unwrap();
+    let last_poll = account.last_poll().unwrap();
+
+    let config = Config::store([account].iter()).unwrap();
+    let accounts = Config::load(&[null_backed], config.as_bytes()).unwrap();
+
+    let restored = accounts[0].0.last_poll().unwrap();
+    let expected = from_unix_secs(to_unix_secs(last_poll).unwrap()).unwrap();
+    assert_eq!(restored, expected);
+    assert!(accounts[0].0.last_poll_attempt().unwrap() <= restored);
+    assert!(!accounts[0]
+        .0
+        .is_poll_overdue(restored, Duration::from_secs(60)));
+    assert!(accounts[0]
+        .0
+        .is_poll_overdue(restored + Duration::from_secs(61), Duration::from_secs(60)));
+}
+
+#[test]
+fn test_config_restores_created_at() {
+    let backends = [crate::backend::null::new_backend(&[])];
+    let account = Account::new(backends[0].clone(), "foo");
+    let created_at = account.created_at().unwrap();
+    assert!(SystemTime::now().duration_since(created_at).unwrap() < Duration::from_secs(60));
+
+    let config = Config::store([account].iter()).unwrap();
+    let accounts = Config::load(&backends, config.as_bytes()).unwrap();
+    assert_eq!(
+        accounts[0].0.created_at(),
+        from_unix_secs(to_unix_secs(created_at).unwrap())
+    );
+
+    let old_config = r#"{"accounts":[{"email":"foo","backend":"Null Backend","value":null}]}"#;
+    let accounts = Config::load(&backends, old_config.as_bytes()).unwrap();
+    assert!(accounts[0].0.created_at().is_none());
+}
+
+#[test]
+fn test_config_restores_notify_cooldown() {
+    let backends = [crate::backend::null::new_backend(&[])];
+    let mut account = Account::new(backends[0].clone(), "foo");
+    account.set_notify_cooldown(Some(Duration::from_secs(300)));
+
+    let config = Config::store([account].iter()).unwrap();
+    let accounts = Config::load(&backends, config.as_bytes()).unwrap();
+    assert_eq!(
+        accounts[0].0.notify_cooldown(),
+        Some(Duration::from_secs(300))
+    );
+}
+
+#[test]
+fn test_config_tolerates_out_of_range_values() {
+    let backends = [crate::backend::null::new_backend(&[])];
+    let config = format!(
+        r#"{{"accounts":[{{"email":"foo","backend":"Null Backend","value":null,"last_poll":{max},"last_poll_attempt":{max},"created_at":{max},"notify_cooldown":{max}}}]}}"#,
+        max = u64::MAX
+    );
+
+    let accounts = Config::load(&backends, config.as_bytes()).unwrap();
+    let account = &accounts[0].0;
+    assert!(account.last_poll().is_none());
+    assert!(account.last_poll_attempt().is_none());
+    assert!(account.created_at().is_none());
+    assert_eq!(account.notify_cooldown(), Some(crate::MAX_NOTIFY_COOLDOWN));
+}
+
+#[test]
+fn test_config_partial_load_skips_broken_accounts() {
+    let backends = [crate::backend::null::new_backend(&[])];
+    let config = r#"{"accounts":[
+        {"email":"foo","backend":"Null Backend","value":null},
+        {"email":"bar","backend":"Missing Backend","value":null},
+        {"email":"baz","backend":"Null Backend","value":{"invalid":true}}
+    ]}"#;
+
+    assert!(Config::load(&backends, config.as_bytes()).is_err());
+
+    let (accounts, errors) = Config::load_partial(&backends, config.as_bytes()).unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].0.email(), "foo");
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        errors[0].1,
+        ConfigLoadError::BackendNotFound { .. }
+    ));
+    assert_eq!(errors[0].1.account(), Some("bar"));
+    assert_eq!(errors[0].0.email(), "bar");
+    assert!(matches!(errors[1].1, ConfigLoadError::BackendConfig { .. }));
+    assert_eq!(errors[1].1.account(), Some("baz"));
+    assert_eq!(errors[1].0.email(), "baz");
+}
+
+#[test]
+fn test_config_partial_load_keeps_broken_accounts_on_store() {
+    let backends = [crate::backend::null::new_backend(&[])];
+    let config = r#"{"accounts":[
+        {"email":"foo","backend":"Null Backend","value":null},
+        {"email":"bar","backend":"Missing Backend","value":{"uid":"bar"},"created_at":10}
+    ]}"#;
+
+    let (accounts, errors) = Config::load_partial(&backends, config.as_bytes()).unwrap();
+    assert_eq!(accounts.len(), 1);
+    let unloaded = errors.into_iter().map(|(a, _)| a).collect::<Vec<_>>();
+    assert_eq!(unloaded.len(), 1);
+
+    let config =
+        Config::store_with_unloaded(accounts.iter().map(|(a, _)| a), unloaded.iter()).unwrap();
+
+    let (accounts, errors) = Config::load_partial(&backends, config.as_bytes()).unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].0.email(), "foo");
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0].1,
+        ConfigLoadError::BackendNotFound { .. }
+    ));
+    assert_eq!(errors[0].0.email(), "bar");
+    assert_eq!(errors[0].0.backend(), "Missing Backend");
+
+    let json = serde_json::from_str::<serde_json::Value>(&config).unwrap();
+    let bar = &json["accounts"][1];
+    assert_eq!(bar["value"], serde_json::json!({"uid": "bar"}));
+    assert_eq!(bar["created_at"], 10);
+
+    // An account added again with the same email replaces the broken entry.
+    let account = Account::new(backends[0].clone(), "bar");
+    let config = Config::store_with_unloaded([account].iter(), unloaded.iter()).unwrap();
+    let accounts = Config::load(&backends, config.as_bytes()).unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].0.backend().name(), "Null Backend");
+}
@@ -54,7 +54,8 @@ impl TryFrom<&[u8]> for EncryptionKey {
     }
 }
 
-/// Default encryption/decryption using chacha20poly1305.
+/// Default encryption/decryption using chacha20poly1305. Since this is an authenticated
+/// encryption scheme, decrypting corrupted or tampered data fails instead of producing garbage.
 pub struct DefaultEncryption {
     key: Secret<EncryptionKey>,
 }
@@ -89,7 +90,7 @@ impl Encryption for DefaultEncryption {
         let cipher = ChaCha20Poly1305::new(&self.key.expose_secret().0);
         let decrypted = cipher
             .decrypt(nonce, &bytes[0..data_len])
-            .map_err(|e| anyhow!(e))?;
+            .map_err(|_| anyhow!("Data is corrupted or was not encrypted with this key"))?;
         Ok(decrypted)
     }
 }
@@ -103,3 +104,13 @@ fn test_encrypt_decrypt() {
     let decrypted = encryptor.decrypt(&encrypted).unwrap();
     assert_eq!(decrypted.as_slice(), value);
 }
+
+#[test]
+fn test_decrypt_tampered_data_fails() {
+    let value = b"Hello World!!";
+    let key = EncryptionKey::new();
+    let encryptor = DefaultEncryption::new(key);
+    let mut encrypted = encryptor.encrypt(value).unwrap();
+    encrypted[0] ^= 0x1;
+    assert!(encryptor.decrypt(&encrypted).is_err());
+}
@@ -1,7 +1,10 @@
 use crate::backend::null::{new_backend, NullTestAccount};
-use crate::backend::Backend;
-use crate::{Account, Notification, Notifier, ObserverBuilder};
+use crate::backend::{Backend, BackendError, MockAccount};
+use crate::{
+    Account, AccountState, Config, Notification, Notifier, ObserverAccountStatus, ObserverBuilder,
+};
 use crate::{MockNotifier, Observer};
+use anyhow::anyhow;
 use mockall::Sequence;
 use proton_api_rs::tokio;
 use std::future::Future;
@@ -14,6 +17,7 @@ async fn new_backend_and_account() -> (Arc<dyn Backend>, Account) {
         password: "bar".to_string(),
         totp: None,
         wait_time: None,
+        ..Default::default()
     };
     let backend = new_backend(&[accounts]);
     let mut account = Account::new(backend.clone(), "foo");
@@ -268,6 +272,144 @@ async fn removing_account_produces_remove_notification() {
     .await;
 }
 
+#[tokio::test]
+async fn renaming_account_keeps_it_observed() {
+    let (_, account) = new_backend_and_account().await;
+
+    let mut notifier = MockNotifier::new();
+    notifier.expect_notify().times(..).return_const(());
+
+    let notifier: Box<dyn Notifier> = Box::new(notifier);
+
+    with_observer(
+        Duration::from_secs(60),
+        notifier,
+        move |observer| async move {
+            observer.add_account(account).await.unwrap();
+            observer.rename_account("foo", "bar").await.unwrap();
+            observer.rename_account("bar", "bar").await.unwrap();
+            observer
+                .rename_account("foo", "baz")
+                .await
+                .expect_err("old email should no longer exist");
+
+            let accounts = observer.get_accounts().await.unwrap();
+            assert_eq!(accounts.len(), 1);
+            assert_eq!(accounts[0].email, "bar");
+            assert_eq!(accounts[0].status, ObserverAccountStatus::Online);
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn accounts_needing_attention_lists_logged_out_and_errored_accounts() {
+    let backend = new_backend(&[
+        NullTestAccount {
+            email: "foo".to_string(),
+            password: "foo".to_string(),
+            totp: None,
+            wait_time: None,
+            ..Default::default()
+        },
+        NullTestAccount {
+            email: "bar".to_string(),
+            password: "bar".to_string(),
+            totp: None,
+            wait_time: None,
+            ..Default::default()
+        },
+    ]);
+    let mut foo = Account::new(backend.clone(), "foo");
+    foo.login("foo").await.unwrap();
+    let mut bar = Account::new(backend.clone(), "bar");
+    bar.login("bar").await.unwrap();
+    let mut mock_account = MockAccount::new();
+    mock_account
+        .expect_check()
+        .returning(|| Err(BackendError::Request(anyhow!("request failed"))));
+    mock_account.expect_display_name().returning(|| None);
+    let baz = Account::with_state(
+        backend,
+        "baz",
+        AccountState::LoggedIn(Box::new(mock_account)),
+    );
+
+    let mut notifier = MockNotifier::new();
+    notifier.expect_notify().times(..).return_const(());
+
+    let notifier: Box<dyn Notifier> = Box::new(notifier);
+
+    with_observer(
+        Duration::from_millis(10),
+        notifier,
+        move |observer| async move {
+            observer.add_account(foo).await.unwrap();
+            observer.add_account(bar).await.unwrap();
+            assert!(observer
+                .get_accounts_needing_attention()
+                .await
+                .unwrap()
+                .is_empty());
+
+            observer.add_account(baz).await.unwrap();
+            observer.logout_account("bar").await.unwrap();
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            let mut accounts = observer.get_accounts_needing_attention().await.unwrap();
+            accounts.sort_by(|a, b| a.email.cmp(&b.email));
+            assert_eq!(accounts.len(), 2);
+            assert_eq!(accounts[0].email, "bar");
+            assert_eq!(accounts[0].status, ObserverAccountStatus::LoggedOut);
+            assert_eq!(accounts[1].email, "baz");
+            assert_eq!(accounts[1].status, ObserverAccountStatus::Error);
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn generated_config_keeps_unloaded_accounts() {
+    let backends = [new_backend(&[])];
+    let config = r#"{"accounts":[
+        {"email":"foo","backend":"Null Backend","value":null},
+        {"email":"bar","backend":"Missing Backend","value":null}
+    ]}"#;
+    let (accounts, errors) = Config::load_partial(&backends, config.as_bytes()).unwrap();
+    let unloaded = errors.into_iter().map(|(a, _)| a).collect::<Vec<_>>();
+
+    let mut notifier = MockNotifier::new();
+    notifier.expect_notify().times(..).return_const(());
+    let (observer, task) = ObserverBuilder::new(Box::new(notifier))
+        .unloaded_accounts(unloaded)
+        .build();
+    let h = tokio::spawn(task);
+
+    for (account, _) in accounts {
+        observer.add_account(account).await.unwrap();
+    }
+
+    let config = observer.generate_config().await.unwrap();
+    let (accounts, errors) = Config::load_partial(&backends, config.as_bytes()).unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0.email(), "bar");
+
+    // Adding the account again replaces the entry which failed to load.
+    observer
+        .add_account(Account::new(backends[0].clone(), "bar"))
+        .await
+        .unwrap();
+    observer.remove_account("bar").await.unwrap();
+    let config = observer.generate_config().await.unwrap();
+    let accounts = Config::load(&backends, config.as_bytes()).unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].0.email(), "foo");
+
+    observer.shutdown_worker().await.unwrap();
+    h.await.unwrap();
+}
+
 async fn with_observer<F, T>(poll_interval: Duration, notifier: Box<dyn Notifier>, f: F)
 where
     F: FnOnce(Observer) -> T,
@@ -1,18 +1,22 @@
+use crate::backend::Backend;
 use crate::observer::rpc::{
-    AddAccountRequest, GenConfigRequest, GetAccountListRequest, LogoutAccountRequest, ObserverPRC,
-    ObserverRequest, RemoveAccountRequest,
+    AddAccountRequest, ChangeBackendRequest, GenConfigRequest, GetAccountListRequest,
+    LogoutAccountRequest, LogoutAllAccountsRequest, ObserverPRC, ObserverRequest,
+    RemoveAccountRequest, RenameAccountRequest, ResyncAccountRequest, SetNotifyCooldownRequest,
+    SetNotifyFoldersRequest, VerifyCredentialsRequest,
 };
 use crate::observer::worker::Worker;
-use crate::{Account, AccountError, ConfigGenError, Notifier};
+use crate::{Account, AccountError, ChangeBackendError, ConfigGenError, Notifier, UnloadedAccount};
 use proton_api_rs::tokio::sync::mpsc::Sender;
 use std::fmt::Formatter;
 use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Clone)]
-pub struct Observer(Arc<Sender<ObserverRequest>>);
+pub struct Observer(Arc<Sender<ObserverRequest>>, Arc<AtomicBool>);
 
 /// Account status for the accounts being watched by the observer.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -23,6 +27,8 @@ pub enum ObserverAccountStatus {
     Online,
     /// The account is logged out or the session expired.
     LoggedOut,
+    /// The last poll of the account failed with an error.
+    Error,
 }
 
 impl std::fmt::Display for ObserverAccountStatus {
@@ -37,16 +43,36 @@ impl std::fmt::Display for ObserverAccountStatus {
             ObserverAccountStatus::LoggedOut => {
                 write!(f, "LoggedOut")
             }
+            ObserverAccountStatus::Error => {
+                write!(f, "Error")
+            }
         }
     }
 }
 
+/// Per account results of [Observer::logout_all](fn@Observer::logout_all).
+pub type LogoutAllResults = Vec<(String, Result<(), ObserverError>)>;
+
 /// Account info for active accounts in the [Observer](struct@Observer).
 #[derive(Debug, Clone)]
 pub struct ObserverAccount {
     pub email: String,
     pub status: ObserverAccountStatus,
     pub backend: String,
+    pub display_name: Option<String>,
+    /// Error reported by the last poll, if it failed.
+    pub last_error: Option<String>,
+}
+
+impl ObserverAccount {
+    /// Whether the user needs to act on this account, e.g: login again after the session expired
+    /// or look into a poll error.
+    pub fn needs_attention(&self) -> bool {
+        matches!(
+            self.status,
+            ObserverAccountStatus::LoggedOut | ObserverAccountStatus::Error
+        )
+    }
 }
 
 /// Errors returned during observer RPC calls.
@@ -71,6 +97,10 @@ pub enum ObserverError {
     AccountError(#[from] AccountError),
     #[error("Account {0} not found")]
     NoSuchAccount(String),
+    #[error("An account with email {0} already exists")]
+    AccountAlreadyExists(String),
+    #[error("{0}")]
+    ChangeBackend(#[from] ChangeBackendError),
     #[error("Unknown error occurred: {0}")]
     Unknown(
         #[from]
@@ -82,6 +112,7 @@ pub enum ObserverError {
 pub struct ObserverBuilder {
     poll_interval: Duration,
     notifier: Box<dyn Notifier>,
+    unloaded_accounts: Vec<UnloadedAccount>,
 }
 
 impl ObserverBuilder {
@@ -89,6 +120,7 @@ impl ObserverBuilder {
         Self {
             poll_interval: Duration::from_secs(30),
             notifier,
+            unloaded_accounts: Vec::new(),
         }
     }
 
@@ -98,6 +130,13 @@ impl ObserverBuilder {
         self
     }
 
+    /// Config entries of accounts which failed to load. They are kept in the generated config
+    /// until an account with the same email is added.
+    pub fn unloaded_accounts(mut self, unloaded_accounts: Vec<UnloadedAccount>) -> Self {
+        self.unloaded_accounts = unloaded_accounts;
+        self
+    }
+
     pub fn build(self) -> (Observer, impl Future<Output = ()>) {
         Observer::new(self)
     }
@@ -105,8 +144,12 @@ impl ObserverBuilder {
 
 impl Observer {
     fn new(builder: ObserverBuilder) -> (Self, impl Future<Output = ()>) {
-        let (task, sender) = Worker::build(builder.notifier, builder.poll_interval);
-        (Self(Arc::new(sender)), task)
+        let (task, sender, cancel_poll) = Worker::build(
+            builder.notifier,
+            builder.poll_interval,
+            builder.unloaded_accounts,
+        );
+        (Self(Arc::new(sender), cancel_poll), task)
     }
 
     /// Get the list of observed accounts and their status
@@ -116,6 +159,16 @@ impl Observer {
         self.perform_rpc(GetAccountListRequest {}).await
     }
 
+    /// Get the list of observed accounts which need the user's attention. See
+    /// [ObserverAccount::needs_attention](fn@ObserverAccount::needs_attention).
+    pub async fn get_accounts_needing_attention(
+        &self,
+    ) -> Result<Vec<ObserverAccount>, ObserverRPCError<(), ObserverError>> {
+        let mut accounts = self.get_accounts().await?;
+        accounts.retain(|a| a.needs_attention());
+        Ok(accounts)
+    }
+
     /// Add a new account to be observed for new emails.
     pub async fn add_account(
         &self,
@@ -146,6 +199,96 @@ impl Observer {
         .await
     }
 
+    /// Logout all observed accounts. A failure to logout one account does not prevent the others
+    /// from being logged out. Returns the result of the logout for each account.
+    pub async fn logout_all(
+        &self,
+    ) -> Result<LogoutAllResults, ObserverRPCError<(), ObserverError>> {
+        self.perform_rpc(LogoutAllAccountsRequest {}).await
+    }
+
+    /// Change the email of an observed account. The account keeps its session and settings.
+    pub async fn rename_account<T: Into<String>>(
+        &self,
+        email: T,
+        new_email: T,
+    ) -> Result<(), ObserverRPCError<String, ObserverError>> {
+        self.perform_rpc(RenameAccountRequest {
+            email: email.into(),
+            new_email: new_email.into(),
+        })
+        .await
+    }
+
+    /// Move an observed account to another backend, e.g: from one Proton backend variant to
+    /// another, without logging in again. See
+    /// [Account::change_backend](fn@crate::Account::change_backend).
+    pub async fn change_backend<T: Into<String>>(
+        &self,
+        email: T,
+        backend: Arc<dyn Backend>,
+    ) -> Result<(), ObserverRPCError<String, ObserverError>> {
+        self.perform_rpc(ChangeBackendRequest {
+            email: email.into(),
+            backend,
+        })
+        .await
+    }
+
+    /// Restart change tracking for an account from the backend's latest state. Messages which
+    /// arrived before the resync will not be reported.
+    pub async fn resync_account<T: Into<String>>(
+        &self,
+        email: T,
+    ) -> Result<(), ObserverRPCError<String, ObserverError>> {
+        self.perform_rpc(ResyncAccountRequest {
+            email: email.into(),
+        })
+        .await
+    }
+
+    /// Override which folders produce new email notifications for an observed account. See
+    /// [Account::set_notify_folders](fn@crate::Account::set_notify_folders).
+    pub async fn set_notify_folders<T: Into<String>>(
+        &self,
+        email: T,
+        include: Vec<String>,
+        exclude: Vec<String>,
+    ) -> Result<(), ObserverRPCError<String, ObserverError>> {
+        self.perform_rpc(SetNotifyFoldersRequest {
+            email: email.into(),
+            include,
+            exclude,
+        })
+        .await
+    }
+
+    /// Set the minimum time between two new email notifications for an observed account. See
+    /// [Account::set_notify_cooldown](fn@crate::Account::set_notify_cooldown).
+    pub async fn set_notify_cooldown<T: Into<String>>(
+        &self,
+        email: T,
+        cooldown: Option<Duration>,
+    ) -> Result<(), ObserverRPCError<String, ObserverError>> {
+        self.perform_rpc(SetNotifyCooldownRequest {
+            email: email.into(),
+            cooldown,
+        })
+        .await
+    }
+
+    /// Check whether the session of an observed account is still valid without polling for new
+    /// email. If the session expired, the account is marked as logged out and false is returned.
+    pub async fn verify_credentials<T: Into<String>>(
+        &self,
+        email: T,
+    ) -> Result<bool, ObserverRPCError<String, ObserverError>> {
+        self.perform_rpc(VerifyCredentialsRequest {
+            email: email.into(),
+        })
+        .await
+    }
+
     /// Signal that the worker should terminate.
     pub async fn shutdown_worker(&self) -> Result<(), ObserverRPCError<(), ObserverError>> {
         if self.0.send(ObserverRequest::Exit).await.is_err() {
@@ -164,6 +307,12 @@ impl Observer {
         Ok(())
     }
 
+    /// Stop the poll that is currently running once the account being checked completes. The
+    /// remaining accounts are checked again by the next poll. Has no effect if no poll is running.
+    pub fn cancel_poll(&self) {
+        self.1.store(true, Ordering::SeqCst);
+    }
+
     /// Resume the execution of the observer.
     pub async fn resume(&self) -> Result<(), ObserverRPCError<(), ObserverError>> {
         if self.0.send(ObserverRequest::Resume).await.is_err() {
@@ -1,12 +1,27 @@
-use crate::{Account, ConfigGenError, ObserverAccount, ObserverError};
+use crate::backend::Backend;
+use crate::{Account, ConfigGenError, LogoutAllResults, ObserverAccount, ObserverError};
 use proton_api_rs::tokio::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// RPC Requests for the `Observer`.
 pub enum ObserverRequest {
     Exit,
     AddAccount(Account, Sender<Result<(), ObserverError>>),
     LogoutAccount(String, Sender<Result<(), ObserverError>>),
+    LogoutAllAccounts(Sender<Result<LogoutAllResults, ObserverError>>),
     RemoveAccount(String, Sender<Result<(), ObserverError>>),
+    RenameAccount(String, String, Sender<Result<(), ObserverError>>),
+    ResyncAccount(String, Sender<Result<(), ObserverError>>),
+    ChangeBackend(String, Arc<dyn Backend>, Sender<Result<(), ObserverError>>),
+    SetNotifyFolders(
+        String,
+        Vec<String>,
+        Vec<String>,
+        Sender<Result<(), ObserverError>>,
+    ),
+    SetNotifyCooldown(String, Option<Duration>, Sender<Result<(), ObserverError>>),
+    VerifyCredentials(String, Sender<Result<bool, ObserverError>>),
     GetAccounts(Sender<Result<Vec<ObserverAccount>, ObserverError>>),
     Pause,
     Resume,
@@ -68,6 +83,149 @@ impl ObserverPRC for LogoutAccountRequest {
     }
 }
 
+#[doc(hidden)]
+pub struct RenameAccountRequest {
+    pub email: String,
+    pub new_email: String,
+}
+
+#[doc(hidden)]
+impl ObserverPRC for RenameAccountRequest {
+    type Output = ();
+    type Error = ObserverError;
+    type SendFailedValue = String;
+
+    fn into_request(self, reply: Sender<Result<Self::Output, Self::Error>>) -> ObserverRequest {
+        ObserverRequest::RenameAccount(self.email, self.new_email, reply)
+    }
+
+    fn recover_send_value(r: ObserverRequest) -> Option<Self::SendFailedValue> {
+        match r {
+            ObserverRequest::RenameAccount(s, _, _) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct ResyncAccountRequest {
+    pub email: String,
+}
+
+#[doc(hidden)]
+impl ObserverPRC for ResyncAccountRequest {
+    type Output = ();
+    type Error = ObserverError;
+    type SendFailedValue = String;
+
+    fn into_request(self, reply: Sender<Result<Self::Output, Self::Error>>) -> ObserverRequest {
+        ObserverRequest::ResyncAccount(self.email, reply)
+    }
+
+    fn recover_send_value(r: ObserverRequest) -> Option<Self::SendFailedValue> {
+        match r {
+            ObserverRequest::ResyncAccount(s, _) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct ChangeBackendRequest {
+    pub email: String,
+    pub backend: Arc<dyn Backend>,
+}
+
+#[doc(hidden)]
+impl ObserverPRC for ChangeBackendRequest {
+    type Output = ();
+    type Error = ObserverError;
+    type SendFailedValue = String;
+
+    fn into_request(self, reply: Sender<Result<Self::Output, Self::Error>>) -> ObserverRequest {
+        ObserverRequest::ChangeBackend(self.email, self.backend, reply)
+    }
+
+    fn recover_send_value(r: ObserverRequest) -> Option<Self::SendFailedValue> {
+        match r {
+            ObserverRequest::ChangeBackend(s, _, _) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct SetNotifyFoldersRequest {
+    pub email: String,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+#[doc(hidden)]
+impl ObserverPRC for SetNotifyFoldersRequest {
+    type Output = ();
+    type Error = ObserverError;
+    type SendFailedValue = String;
+
+    fn into_request(self, reply: Sender<Result<Self::Output, Self::Error>>) -> ObserverRequest {
+        ObserverRequest::SetNotifyFolders(self.email, self.include, self.exclude, reply)
+    }
+
+    fn recover_send_value(r: ObserverRequest) -> Option<Self::SendFailedValue> {
+        match r {
+            ObserverRequest::SetNotifyFolders(s, _, _, _) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct SetNotifyCooldownRequest {
+    pub email: String,
+    pub cooldown: Option<Duration>,
+}
+
+#[doc(hidden)]
+impl ObserverPRC for SetNotifyCooldownRequest {
+    type Output = ();
+    type Error = ObserverError;
+    type SendFailedValue = String;
+
+    fn into_request(self, reply: Sender<Result<Self::Output, Self::Error>>) -> ObserverRequest {
+        ObserverRequest::SetNotifyCooldown(self.email, self.cooldown, reply)
+    }
+
+    fn recover_send_value(r: ObserverRequest) -> Option<Self::SendFailedValue> {
+        match r {
+            ObserverRequest::SetNotifyCooldown(s, _, _) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct VerifyCredentialsRequest {
+    pub email: String,
+}
+
+#[doc(hidden)]
+impl ObserverPRC for VerifyCredentialsRequest {
+    type Output = bool;
+    type Error = ObserverError;
+    type SendFailedValue = String;
+
+    fn into_request(self, reply: Sender<Result<Self::Output, Self::Error>>) -> ObserverRequest {
+        ObserverRequest::VerifyCredentials(self.email, reply)
+    }
+
+    fn recover_send_value(r: ObserverRequest) -> Option<Self::SendFailedValue> {
+        match r {
+            ObserverRequest::VerifyCredentials(s, _) => Some(s),
+            _ => None,
+        }
+    }
+}
+
 #[doc(hidden)]
 pub struct AddAccountRequest {
     pub account: Account,
@@ -107,6 +265,23 @@ impl ObserverPRC for GetAccountListRequest {
     }
 }
 
+#[doc(hidden)]
+pub struct LogoutAllAccountsRequest {}
+
+impl ObserverPRC for LogoutAllAccountsRequest {
+    type Output = LogoutAllResults;
+    type Error = ObserverError;
+    type SendFailedValue = ();
+
+    fn into_request(self, reply: Sender<Result<Self::Output, Self::Error>>) -> ObserverRequest {
+        ObserverRequest::LogoutAllAccounts(reply)
+    }
+
+    fn recover_send_value(_: ObserverRequest) -> Option<Self::SendFailedValue> {
+        Some(())
+    }
+}
+
 #[doc(hidden)]
 pub struct GenConfigRequest {}
 
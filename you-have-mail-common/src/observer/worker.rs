@@ -2,14 +2,20 @@ use crate::backend::BackendError;
 use crate::observer::rpc::ObserverRequest;
 use crate::{
     Account, AccountError, Config, Notification, Notifier, ObserverAccount, ObserverAccountStatus,
-    ObserverError,
+    ObserverError, UnloadedAccount,
 };
+use anyhow::anyhow;
 use proton_api_rs::log::{debug, error};
 use proton_api_rs::tokio;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::future::Future;
-use std::time::Duration;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
 use tokio::sync::mpsc::{Receiver, Sender};
 
 /// Observer background worker. Handles RPC commands and polls the accounts for updates.
@@ -18,12 +24,20 @@ pub struct Worker {
     notifier: Box<dyn Notifier>,
     poll_interval: Duration,
     paused: bool,
+    /// Set to stop the current poll before the remaining accounts are checked.
+    cancel_poll: Arc<AtomicBool>,
+    /// Config entries of accounts which failed to load, written back with the generated config.
+    unloaded_accounts: Vec<UnloadedAccount>,
 }
 
 /// Represents and active account.
 struct WorkerAccount {
     account: Account,
     status: ObserverAccountStatus,
+    /// Error of the last poll, cleared once a poll succeeds.
+    last_error: Option<String>,
+    /// New emails found while notifications were suppressed by the notify cooldown.
+    pending_notify_count: usize,
 }
 
 impl Worker {
@@ -33,16 +47,25 @@ impl Worker {
             poll_interval,
             accounts: HashMap::new(),
             paused: false,
+            cancel_poll: Arc::new(AtomicBool::new(false)),
+            unloaded_accounts: Vec::new(),
         }
     }
 
     pub fn build(
         notifier: Box<dyn Notifier>,
         poll_interval: Duration,
-    ) -> (impl Future<Output = ()>, Sender<ObserverRequest>) {
+        unloaded_accounts: Vec<UnloadedAccount>,
+    ) -> (
+        impl Future<Output = ()>,
+        Sender<ObserverRequest>,
+        Arc<AtomicBool>,
+    ) {
         let (sender, receiver) = proton_api_rs::tokio::sync::mpsc::channel::<ObserverRequest>(5);
-        let observer = Self::new(notifier, poll_interval);
-        (observer_task(observer, receiver), sender)
+        let mut observer = Self::new(notifier, poll_interval);
+        observer.unloaded_accounts = unloaded_accounts;
+        let cancel_poll = observer.cancel_poll.clone();
+        (observer_task(observer, receiver), sender, cancel_poll)
     }
 
     #[cfg(test)]
@@ -52,6 +75,8 @@ impl Worker {
             WorkerAccount {
                 account,
                 status: ObserverAccountStatus::Online,
+                last_error: None,
+                pending_notify_count: 0,
             },
         );
     }
@@ -65,7 +90,8 @@ impl Worker {
                     account.email(),
                     account_status
                 );
-                let result = match self.accounts.entry(account.email().to_string()) {
+                let email = account.email().to_string();
+                let result = match self.accounts.entry(email.clone()) {
                     Entry::Occupied(mut v) => {
                         if v.get().status == ObserverAccountStatus::LoggedOut {
                             self.notifier
@@ -73,6 +99,8 @@ impl Worker {
                             v.insert(WorkerAccount {
                                 account,
                                 status: account_status,
+                                last_error: None,
+                                pending_notify_count: 0,
                             });
                             Ok(())
                         } else {
@@ -85,11 +113,18 @@ impl Worker {
                         v.insert(WorkerAccount {
                             account,
                             status: account_status,
+                            last_error: None,
+                            pending_notify_count: 0,
                         });
                         Ok(())
                     }
                 };
 
+                if result.is_ok() {
+                    // The account replaces any entry of the same email which failed to load.
+                    self.unloaded_accounts.retain(|a| a.email() != email);
+                }
+
                 if reply.send(result).await.is_err() {
                     error!("Failed to send reply for remove account request");
                 }
@@ -99,7 +134,12 @@ impl Worker {
             ObserverRequest::LogoutAccount(email, reply) => {
                 debug!("Logout account request: account {email}");
                 let result = if let Some(account) = self.accounts.get_mut(&email) {
-                    let r = account.account.logout().await.map_err(|e| e.into());
+                    let r = account
+                        .account
+                        .logout()
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| e.into());
                     if r.is_ok() {
                         account.status = ObserverAccountStatus::LoggedOut;
                         self.notifier.notify(Notification::AccountLoggedOut(&email));
@@ -115,10 +155,33 @@ impl Worker {
 
                 false
             }
+            ObserverRequest::LogoutAllAccounts(reply) => {
+                debug!("Logout all accounts request");
+                let mut results = Vec::with_capacity(self.accounts.len());
+                for (email, account) in &mut self.accounts {
+                    let r = account
+                        .account
+                        .logout()
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| e.into());
+                    if r.is_ok() {
+                        account.status = ObserverAccountStatus::LoggedOut;
+                        self.notifier.notify(Notification::AccountLoggedOut(email));
+                    }
+                    results.push((email.clone(), r));
+                }
+
+                if reply.send(Ok(results)).await.is_err() {
+                    error!("Failed to send reply for logout all accounts request");
+                }
+
+                false
+            }
             ObserverRequest::RemoveAccount(email, reply) => {
                 debug!("Remove account request: account {email}");
                 let result = if let Some(mut account) = self.accounts.remove(&email) {
-                    let r = account.account.logout().await;
+                    let r = account.account.logout().await.map(|_| ());
                     if r.is_ok() {
                         self.notifier.notify(Notification::AccountRemoved(&email));
                     }
@@ -133,6 +196,121 @@ impl Worker {
 
                 false
             }
+            ObserverRequest::RenameAccount(email, new_email, reply) => {
+                debug!("Rename account request: account {email} new email {new_email}");
+                let result = if email == new_email {
+                    if self.accounts.contains_key(&email) {
+                        Ok(())
+                    } else {
+                        Err(ObserverError::NoSuchAccount(email))
+                    }
+                } else if self.accounts.contains_key(&new_email) {
+                    Err(ObserverError::AccountAlreadyExists(new_email))
+                } else if let Some(mut account) = self.accounts.remove(&email) {
+                    account.account.set_email(new_email.clone());
+                    self.accounts.insert(new_email, account);
+                    Ok(())
+                } else {
+                    Err(ObserverError::NoSuchAccount(email))
+                };
+
+                if reply.send(result).await.is_err() {
+                    error!("Failed to send reply for rename account request");
+                }
+
+                false
+            }
+            ObserverRequest::ResyncAccount(email, reply) => {
+                debug!("Resync account request: account {email}");
+                let result = if let Some(account) = self.accounts.get_mut(&email) {
+                    account.account.resync().map_err(|e| e.into())
+                } else {
+                    Err(ObserverError::NoSuchAccount(email))
+                };
+
+                if reply.send(result).await.is_err() {
+                    error!("Failed to send reply for resync account request");
+                }
+
+                false
+            }
+            ObserverRequest::ChangeBackend(email, backend, reply) => {
+                debug!(
+                    "Change backend request: account {email} backend {}",
+                    backend.name()
+                );
+                let result = if let Some(account) = self.accounts.get_mut(&email) {
+                    account
+                        .account
+                        .change_backend(backend)
+                        .await
+                        .map_err(|e| e.into())
+                } else {
+                    Err(ObserverError::NoSuchAccount(email))
+                };
+
+                if reply.send(result).await.is_err() {
+                    error!("Failed to send reply for change backend request");
+                }
+
+                false
+            }
+            ObserverRequest::SetNotifyFolders(email, include, exclude, reply) => {
+                debug!("Set notify folders request: account {email}");
+                let result = if let Some(account) = self.accounts.get_mut(&email) {
+                    account
+                        .account
+                        .set_notify_folders(include, exclude)
+                        .map_err(|e| e.into())
+                } else {
+                    Err(ObserverError::NoSuchAccount(email))
+                };
+
+                if reply.send(result).await.is_err() {
+                    error!("Failed to send reply for set notify folders request");
+                }
+
+                false
+            }
+            ObserverRequest::SetNotifyCooldown(email, cooldown, reply) => {
+                debug!("Set notify cooldown request: account {email} cooldown {cooldown:?}");
+                let result = if let Some(account) = self.accounts.get_mut(&email) {
+                    account.account.set_notify_cooldown(cooldown);
+                    Ok(())
+                } else {
+                    Err(ObserverError::NoSuchAccount(email))
+                };
+
+                if reply.send(result).await.is_err() {
+                    error!("Failed to send reply for set notify cooldown request");
+                }
+
+                false
+            }
+            ObserverRequest::VerifyCredentials(email, reply) => {
+                debug!("Verify credentials request: account {email}");
+                let result = if let Some(wa) = self.accounts.get_mut(&email) {
+                    match wa.account.verify_credentials().await {
+                        Ok(valid) => {
+                            if !valid && wa.status != ObserverAccountStatus::LoggedOut {
+                                self.notifier
+                                    .notify(Notification::AccountLoggedOut(wa.account.email()));
+                                wa.status = ObserverAccountStatus::LoggedOut;
+                            }
+                            Ok(valid)
+                        }
+                        Err(e) => Err(e.into()),
+                    }
+                } else {
+                    Err(ObserverError::NoSuchAccount(email))
+                };
+
+                if reply.send(result).await.is_err() {
+                    error!("Failed to send reply for verify credentials request");
+                }
+
+                false
+            }
             ObserverRequest::GetAccounts(reply) => {
                 debug!("Get accounts request");
                 let accounts = self
@@ -142,6 +320,8 @@ impl Worker {
                         email: k.clone(),
                         status: v.status,
                         backend: v.account.backend().name().to_string(),
+                        display_name: v.account.display_name().map(|n| n.to_string()),
+                        last_error: v.last_error.clone(),
                     })
                     .collect::<Vec<_>>();
 
@@ -164,7 +344,10 @@ impl Worker {
             }
             ObserverRequest::GenConfig(reply) => {
                 debug!("Gen config request");
-                let r = Config::store(self.accounts.values().map(|a| &a.account));
+                let r = Config::store_with_unloaded(
+                    self.accounts.values().map(|a| &a.account),
+                    self.unloaded_accounts.iter(),
+                );
 
                 if reply.send(r).await.is_err() {
                     error!("Failed to send reply for gen config request");
@@ -180,14 +363,21 @@ impl Worker {
             return;
         }
 
+        self.cancel_poll.store(false, Ordering::SeqCst);
         for wa in &mut self.accounts.values_mut() {
+            if self.cancel_poll.load(Ordering::SeqCst) {
+                debug!("Poll cancelled, skipping remaining accounts");
+                break;
+            }
+
             // Track logged out status if for some reason something slips through.
             if wa.account.is_logged_out() {
                 wa.status = ObserverAccountStatus::LoggedOut;
             }
 
-            // Skip accounts which are not logged in.
+            // Skip accounts which are not logged in, but still record the poll attempt.
             if wa.status == ObserverAccountStatus::LoggedOut {
+                wa.account.set_last_poll_attempt(Some(SystemTime::now()));
                 continue;
             }
 
@@ -196,19 +386,43 @@ impl Worker {
                 wa.account.email(),
                 wa.account.backend().name()
             );
-            match wa.account.check().await {
+            // Don't let a panic in one account's check prevent the others from being polled.
+            let result = match CatchUnwind(Box::pin(wa.account.check())).await {
+                Ok(r) => r,
+                Err(_) => Err(AccountError::Backend(BackendError::Unknown(anyhow!(
+                    "Account check panicked"
+                )))),
+            };
+
+            match result {
                 Ok(check) => {
+                    wa.last_error = None;
                     if wa.status != ObserverAccountStatus::Online {
                         self.notifier
                             .notify(Notification::AccountOnline(wa.account.email()))
                     }
                     wa.status = ObserverAccountStatus::Online;
-                    if check.count > 0 {
-                        self.notifier.notify(Notification::NewEmail {
-                            account: wa.account.email(),
-                            backend: wa.account.backend().name(),
-                            count: check.count,
-                        });
+                    let now = SystemTime::now();
+                    if wa.account.is_notify_suppressed(now) {
+                        if check.count > 0 {
+                            debug!(
+                                "Skipping new email notification account={} backend={}: cooldown",
+                                wa.account.email(),
+                                wa.account.backend().name()
+                            );
+                            wa.pending_notify_count += check.count;
+                        }
+                    } else {
+                        let count = wa.pending_notify_count + check.count;
+                        if count > 0 {
+                            self.notifier.notify(Notification::NewEmail {
+                                account: wa.account.email(),
+                                backend: wa.account.backend().name(),
+                                count,
+                            });
+                            wa.account.set_last_notified(Some(now));
+                            wa.pending_notify_count = 0;
+                        }
                     }
                 }
                 Err(e) => {
@@ -218,11 +432,12 @@ impl Worker {
                         wa.account.backend().name(),
                         e
                     );
+                    wa.last_error = Some(e.to_string());
                     if let AccountError::Backend(be) = &e {
                         match be {
                             BackendError::LoggedOut => {
                                 if wa.status == ObserverAccountStatus::LoggedOut {
-                                    return;
+                                    continue;
                                 }
                                 self.notifier
                                     .notify(Notification::AccountLoggedOut(wa.account.email()));
@@ -230,15 +445,17 @@ impl Worker {
                             }
                             BackendError::Offline => {
                                 if wa.status == ObserverAccountStatus::Offline {
-                                    return;
+                                    continue;
                                 }
                                 self.notifier
                                     .notify(Notification::AccountOffline(wa.account.email()));
                                 wa.status = ObserverAccountStatus::Offline;
                             }
-                            _ => self
-                                .notifier
-                                .notify(Notification::AccountError(wa.account.email(), e)),
+                            _ => {
+                                wa.status = ObserverAccountStatus::Error;
+                                self.notifier
+                                    .notify(Notification::AccountError(wa.account.email(), e))
+                            }
                         }
                     }
                 }
@@ -247,6 +464,21 @@ impl Worker {
     }
 }
 
+/// Future adapter which resolves to an error if the inner future panics while being polled.
+struct CatchUnwind<F>(F);
+
+impl<F: Future + Unpin> Future for CatchUnwind<F> {
+    type Output = std::thread::Result<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match std::panic::catch_unwind(AssertUnwindSafe(|| Pin::new(&mut self.0).poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(v)) => Poll::Ready(Ok(v)),
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
+
 async fn observer_task(mut observer: Worker, mut receiver: Receiver<ObserverRequest>) {
     debug!("Starting observer loop");
     let sleep = tokio::time::interval(observer.poll_interval);
@@ -272,12 +504,20 @@ async fn observer_task(mut observer: Worker, mut receiver: Receiver<ObserverRequ
 
 #[cfg(test)]
 mod tests {
-    use crate::backend::{BackendError, MockAccount, NewEmailReply};
+    use crate::backend::{
+        Backend, BackendError, MockAccount, MockAuthRefresher, MockBackend, NewEmailReply,
+    };
+    use crate::observer::rpc::ObserverRequest;
     use crate::observer::worker::Worker;
-    use crate::{Account, AccountState, MockNotifier, Notification};
+    use crate::{
+        Account, AccountState, MockNotifier, Notification, ObserverAccountStatus, ObserverError,
+    };
+    use anyhow::anyhow;
     use mockall::Sequence;
     use proton_api_rs::tokio;
-    use std::time::Duration;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
 
     #[tokio::test]
     async fn worker_notifies_offline_only_once() {
@@ -395,6 +635,501 @@ mod tests {
         worker.poll_accounts().await;
         worker.poll_accounts().await;
     }
+
+    #[tokio::test]
+    async fn worker_records_poll_attempt_of_skipped_logged_out_accounts() {
+        let mut notifier = MockNotifier::new();
+        notifier.expect_notify().times(..).return_const(());
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+
+        worker.add_account(Account::new(crate::backend::null::new_backend(&[]), "foo"));
+        assert!(worker.accounts["foo"].account.last_poll_attempt().is_none());
+
+        let before = SystemTime::now();
+        worker.poll_accounts().await;
+
+        let account = &worker.accounts["foo"].account;
+        assert_eq!(
+            worker.accounts["foo"].status,
+            ObserverAccountStatus::LoggedOut
+        );
+        assert!(account.last_poll_attempt().unwrap() >= before);
+        assert!(account.last_poll().is_none());
+    }
+
+    #[tokio::test]
+    async fn worker_keeps_polling_other_accounts_when_one_is_already_offline() {
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|n| matches!(n, Notification::AccountOffline(_)))
+            .times(3)
+            .return_const(());
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+        for email in ["foo", "bar", "baz"] {
+            let mut mock_account = MockAccount::new();
+            mock_account
+                .expect_check()
+                .times(2)
+                .returning(|| Err(BackendError::Offline));
+            worker.add_account(Account::with_state(
+                crate::backend::null::new_backend(&[]),
+                email,
+                AccountState::LoggedIn(Box::new(mock_account)),
+            ));
+        }
+
+        worker.poll_accounts().await;
+        // All accounts are already offline now, none of them may end the poll early.
+        worker.poll_accounts().await;
+    }
+
+    #[tokio::test]
+    async fn worker_keeps_polling_other_accounts_after_panic() {
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|n| matches!(n, Notification::AccountError("foo", _)))
+            .times(1)
+            .return_const(());
+        notifier
+            .expect_notify()
+            .withf(|n| matches!(n, Notification::NewEmail { account: "bar", .. }))
+            .times(1)
+            .return_const(());
+        let mut panicking_account = MockAccount::new();
+        panicking_account
+            .expect_check()
+            .times(1)
+            .returning(|| panic!("malformed server data"));
+        let mut mock_account = MockAccount::new();
+        mock_account
+            .expect_check()
+            .times(1)
+            .returning(|| Ok(NewEmailReply { count: 1 }));
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+
+        worker.add_account(Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(panicking_account)),
+        ));
+        worker.add_account(Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "bar",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        ));
+
+        worker.poll_accounts().await;
+
+        assert_eq!(worker.accounts["foo"].status, ObserverAccountStatus::Error);
+        assert_eq!(worker.accounts["bar"].status, ObserverAccountStatus::Online);
+    }
+
+    #[tokio::test]
+    async fn worker_logout_all_reports_each_account() {
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|n| matches!(n, Notification::AccountLoggedOut("bar")))
+            .times(1)
+            .return_const(());
+        let mut failing_account = MockAccount::new();
+        failing_account
+            .expect_logout()
+            .times(1)
+            .returning(|| Err(BackendError::Offline));
+        let mut mock_account = MockAccount::new();
+        mock_account.expect_logout().times(1).returning(|| Ok(()));
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+
+        worker.add_account(Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(failing_account)),
+        ));
+        worker.add_account(Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "bar",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        ));
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        worker
+            .handle_request(ObserverRequest::LogoutAllAccounts(sender))
+            .await;
+        let mut results = receiver.recv().await.unwrap().unwrap();
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "bar");
+        assert!(results[0].1.is_ok());
+        assert_eq!(results[1].0, "foo");
+        assert!(results[1].1.is_err());
+        assert_eq!(
+            worker.accounts["bar"].status,
+            ObserverAccountStatus::LoggedOut
+        );
+        assert_eq!(worker.accounts["foo"].status, ObserverAccountStatus::Online);
+    }
+
+    #[tokio::test]
+    async fn worker_sets_notify_folders_of_observed_account() {
+        let mut notifier = MockNotifier::new();
+        notifier.expect_notify().times(..).return_const(());
+        let mut mock_account = MockAccount::new();
+        mock_account
+            .expect_set_notify_folders()
+            .withf(|include, exclude| include == &["10"] && exclude == &["20"])
+            .times(1)
+            .return_const(());
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+
+        worker.add_account(Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        ));
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        worker
+            .handle_request(ObserverRequest::SetNotifyFolders(
+                "foo".to_string(),
+                vec!["10".to_string()],
+                vec!["20".to_string()],
+                sender,
+            ))
+            .await;
+        receiver.recv().await.unwrap().unwrap();
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        worker
+            .handle_request(ObserverRequest::SetNotifyFolders(
+                "bar".to_string(),
+                vec![],
+                vec![],
+                sender,
+            ))
+            .await;
+        assert!(matches!(
+            receiver.recv().await.unwrap(),
+            Err(ObserverError::NoSuchAccount(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn worker_change_backend_keeps_polling_account() {
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|n| {
+                matches!(
+                    n,
+                    Notification::NewEmail {
+                        account: "foo",
+                        backend: "Other",
+                        count: 1
+                    }
+                )
+            })
+            .times(1)
+            .return_const(());
+        let mut old_account = MockAccount::new();
+        old_account
+            .expect_auth_refresher_config()
+            .times(1)
+            .returning(|| Ok(serde_json::json!({"session": "foo"})));
+        let mut new_account = MockAccount::new();
+        new_account
+            .expect_check()
+            .times(1)
+            .returning(|| Ok(NewEmailReply { count: 1 }));
+        let mut refresher = MockAuthRefresher::new();
+        refresher
+            .expect_refresh()
+            .times(1)
+            .return_once(move || Ok(AccountState::LoggedIn(Box::new(new_account))));
+        let mut backend = MockBackend::new();
+        backend.expect_name().return_const("Other".to_string());
+        backend
+            .expect_auth_refresher_from_config()
+            .withf(|v| v == &serde_json::json!({"session": "foo"}))
+            .times(1)
+            .return_once(move |_| Ok(Box::new(refresher)));
+        let backend: Arc<dyn Backend> = Arc::new(backend);
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+
+        worker.add_account(Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(old_account)),
+        ));
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        worker
+            .handle_request(ObserverRequest::ChangeBackend(
+                "foo".to_string(),
+                backend.clone(),
+                sender,
+            ))
+            .await;
+        receiver.recv().await.unwrap().unwrap();
+        assert_eq!(worker.accounts["foo"].account.backend().name(), "Other");
+
+        worker.poll_accounts().await;
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        worker
+            .handle_request(ObserverRequest::ChangeBackend(
+                "bar".to_string(),
+                backend,
+                sender,
+            ))
+            .await;
+        assert!(matches!(
+            receiver.recv().await.unwrap(),
+            Err(ObserverError::NoSuchAccount(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn worker_verify_credentials_marks_expired_account_logged_out() {
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|n| matches!(n, Notification::AccountLoggedOut("foo")))
+            .times(1)
+            .return_const(());
+        let mut mock_account = MockAccount::new();
+        mock_account
+            .expect_verify_credentials()
+            .times(1)
+            .returning(|| Err(BackendError::LoggedOut));
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+
+        worker.add_account(Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        ));
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        worker
+            .handle_request(ObserverRequest::VerifyCredentials(
+                "foo".to_string(),
+                sender,
+            ))
+            .await;
+        assert!(!receiver.recv().await.unwrap().unwrap());
+        assert_eq!(
+            worker.accounts["foo"].status,
+            ObserverAccountStatus::LoggedOut
+        );
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        worker
+            .handle_request(ObserverRequest::VerifyCredentials(
+                "foo".to_string(),
+                sender,
+            ))
+            .await;
+        assert!(!receiver.recv().await.unwrap().unwrap());
+    }
+
+    #[tokio::test]
+    async fn worker_cancelled_poll_skips_remaining_accounts() {
+        let mut notifier = MockNotifier::new();
+        notifier.expect_notify().times(..).return_const(());
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+        let checks = Arc::new(AtomicUsize::new(0));
+
+        for email in ["foo", "bar"] {
+            let mut mock_account = MockAccount::new();
+            let cancel_poll = worker.cancel_poll.clone();
+            let checks = checks.clone();
+            mock_account.expect_check().times(0..=1).returning(move || {
+                checks.fetch_add(1, Ordering::SeqCst);
+                cancel_poll.store(true, Ordering::SeqCst);
+                Ok(NewEmailReply { count: 0 })
+            });
+            worker.add_account(Account::with_state(
+                crate::backend::null::new_backend(&[]),
+                email,
+                AccountState::LoggedIn(Box::new(mock_account)),
+            ));
+        }
+
+        worker.poll_accounts().await;
+        assert_eq!(checks.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn worker_suppresses_new_email_notification_during_cooldown() {
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|n| matches!(n, Notification::NewEmail { .. }))
+            .times(1)
+            .return_const(());
+        notifier.expect_notify().times(..).return_const(());
+        let mut mock_account = MockAccount::new();
+        mock_account
+            .expect_check()
+            .times(2)
+            .returning(|| Ok(NewEmailReply { count: 1 }));
+        let mut account = Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        );
+        account.set_notify_cooldown(Some(Duration::from_secs(60)));
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+
+        worker.add_account(account);
+
+        worker.poll_accounts().await;
+        worker.poll_accounts().await;
+        assert!(worker.accounts["foo"].account.last_notified().is_some());
+    }
+
+    #[tokio::test]
+    async fn worker_notifies_pending_new_email_once_cooldown_ends() {
+        let mut notifier = MockNotifier::new();
+        let mut notify_sequence = Sequence::new();
+        notifier
+            .expect_notify()
+            .withf(|n| matches!(n, Notification::NewEmail { count: 1, .. }))
+            .times(1)
+            .in_sequence(&mut notify_sequence)
+            .return_const(());
+        notifier
+            .expect_notify()
+            .withf(|n| matches!(n, Notification::NewEmail { count: 5, .. }))
+            .times(1)
+            .in_sequence(&mut notify_sequence)
+            .return_const(());
+        let mut mock_account = MockAccount::new();
+        let mut check_sequence = Sequence::new();
+        mock_account
+            .expect_check()
+            .times(1)
+            .in_sequence(&mut check_sequence)
+            .returning(|| Ok(NewEmailReply { count: 1 }));
+        mock_account
+            .expect_check()
+            .times(2)
+            .in_sequence(&mut check_sequence)
+            .returning(|| Ok(NewEmailReply { count: 2 }));
+        mock_account
+            .expect_check()
+            .times(1)
+            .in_sequence(&mut check_sequence)
+            .returning(|| Ok(NewEmailReply { count: 1 }));
+        mock_account
+            .expect_check()
+            .times(1)
+            .in_sequence(&mut check_sequence)
+            .returning(|| Ok(NewEmailReply { count: 0 }));
+        let mut account = Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        );
+        account.set_notify_cooldown(Some(Duration::from_secs(60)));
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+
+        worker.add_account(account);
+
+        worker.poll_accounts().await;
+        worker.poll_accounts().await;
+        worker.poll_accounts().await;
+        assert_eq!(worker.accounts["foo"].pending_notify_count, 4);
+
+        // Move the last notification out of the cooldown window.
+        let wa = worker.accounts.get_mut("foo").unwrap();
+        wa.account
+            .set_last_notified(Some(SystemTime::now() - Duration::from_secs(120)));
+        worker.poll_accounts().await;
+        assert_eq!(worker.accounts["foo"].pending_notify_count, 0);
+
+        // Nothing new and nothing pending, no further notification.
+        worker.poll_accounts().await;
+    }
+
+    #[tokio::test]
+    async fn worker_sets_notify_cooldown_of_observed_account() {
+        let mut notifier = MockNotifier::new();
+        notifier.expect_notify().times(..).return_const(());
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+
+        worker.add_account(Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(MockAccount::new())),
+        ));
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        worker
+            .handle_request(ObserverRequest::SetNotifyCooldown(
+                "foo".to_string(),
+                Some(Duration::from_secs(30)),
+                sender,
+            ))
+            .await;
+        receiver.recv().await.unwrap().unwrap();
+        assert_eq!(
+            worker.accounts["foo"].account.notify_cooldown(),
+            Some(Duration::from_secs(30))
+        );
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        worker
+            .handle_request(ObserverRequest::SetNotifyCooldown(
+                "bar".to_string(),
+                None,
+                sender,
+            ))
+            .await;
+        assert!(matches!(
+            receiver.recv().await.unwrap(),
+            Err(ObserverError::NoSuchAccount(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn worker_records_last_poll_error_until_next_success() {
+        let mut notifier = MockNotifier::new();
+        notifier.expect_notify().times(..).return_const(());
+        let mut mock_account = MockAccount::new();
+        let mut mock_sequence = Sequence::new();
+        mock_account
+            .expect_check()
+            .times(1)
+            .in_sequence(&mut mock_sequence)
+            .returning(|| Err(BackendError::Request(anyhow!("bad request"))));
+        mock_account
+            .expect_check()
+            .times(1)
+            .in_sequence(&mut mock_sequence)
+            .returning(|| Ok(NewEmailReply { count: 0 }));
+        let account = Account::with_state(
+            crate::backend::null::new_backend(&[]),
+            "foo",
+            AccountState::LoggedIn(Box::new(mock_account)),
+        );
+        let mut worker = Worker::new(Box::new(notifier), Duration::from_millis(1));
+
+        worker.add_account(account);
+
+        worker.poll_accounts().await;
+        let error = worker.accounts["foo"].last_error.clone().unwrap();
+        assert!(error.contains("bad request"));
+        assert_eq!(worker.accounts["foo"].status, ObserverAccountStatus::Error);
+
+        worker.poll_accounts().await;
+        assert!(worker.accounts["foo"].last_error.is_none());
+        assert_eq!(worker.accounts["foo"].status, ObserverAccountStatus::Online);
+    }
 }
 
 fn account_status_to_observer_account_status(account: &Account) -> ObserverAccountStatus {
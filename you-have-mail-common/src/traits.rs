@@ -27,6 +27,42 @@ pub enum Notification<'a> {
     AccountError(&'a str, AccountError),
 }
 
+impl<'a> Notification<'a> {
+    /// The email of the account this notification is about.
+    pub fn email(&self) -> &'a str {
+        match self {
+            Notification::NewEmail { account, .. } => account,
+            Notification::AccountAdded(email)
+            | Notification::AccountLoggedOut(email)
+            | Notification::AccountRemoved(email)
+            | Notification::AccountOffline(email)
+            | Notification::AccountOnline(email)
+            | Notification::AccountError(email, _) => email,
+        }
+    }
+
+    /// The account's backend name, if the notification carries it.
+    pub fn backend(&self) -> Option<&'a str> {
+        match self {
+            Notification::NewEmail { backend, .. } => Some(backend),
+            _ => None,
+        }
+    }
+
+    /// Number of new emails, if this is a new email notification.
+    pub fn new_email_count(&self) -> Option<usize> {
+        match self {
+            Notification::NewEmail { count, .. } => Some(*count),
+            _ => None,
+        }
+    }
+
+    /// Whether this notification reports an error.
+    pub fn is_error(&self) -> bool {
+        matches!(self, Notification::AccountError(..))
+    }
+}
+
 /// When an email has been received the notifier will be called.
 #[cfg_attr(test, automock)]
 pub trait Notifier: Send + Sync {
@@ -42,3 +78,26 @@ pub struct NullNotifier {}
 impl Notifier for NullNotifier {
     fn notify(&self, _: Notification) {}
 }
+
+#[test]
+fn test_notification_accessors() {
+    let new_email = Notification::NewEmail {
+        account: "foo",
+        backend: "bar",
+        count: 3,
+    };
+    assert_eq!(new_email.email(), "foo");
+    assert_eq!(new_email.backend(), Some("bar"));
+    assert_eq!(new_email.new_email_count(), Some(3));
+    assert!(!new_email.is_error());
+
+    let offline = Notification::AccountOffline("foo");
+    assert_eq!(offline.email(), "foo");
+    assert_eq!(offline.backend(), None);
+    assert_eq!(offline.new_email_count(), None);
+    assert!(!offline.is_error());
+
+    let error = Notification::AccountError("foo", AccountError::InvalidState);
+    assert_eq!(error.email(), "foo");
+    assert!(error.is_error());
+}
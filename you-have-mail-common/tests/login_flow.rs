@@ -9,6 +9,7 @@ async fn test_login_flow() {
         password: "bar".to_string(),
         totp: Some("1234".to_string()),
         wait_time: None,
+        ..Default::default()
     };
     let backend = you_have_mail_common::backend::null::new_backend(&[accounts]);
 
@@ -19,6 +19,8 @@ pub enum ServiceError {
     LoggedOut,
     #[error("Account backend is not reachable")]
     Offline,
+    #[error("Too many attempts, please wait before trying again")]
+    TooManyAttempts,
     #[error("{error}")]
     Config { error: ConfigError },
     #[error("Unknown: {msg}")]
@@ -51,6 +53,7 @@ impl From<yhm::backend::BackendError> for ServiceError {
         match value {
             BackendError::LoggedOut => ServiceError::LoggedOut,
             BackendError::Offline => ServiceError::Offline,
+            BackendError::TooManyAttempts => ServiceError::TooManyAttempts,
             BackendError::Request(e) => ServiceError::RequestError { msg: e.to_string() },
             BackendError::Unknown(e) => ServiceError::Unknown { msg: e.to_string() },
         }
@@ -60,7 +63,9 @@ impl From<yhm::backend::BackendError> for ServiceError {
 impl From<yhm::AccountError> for ServiceError {
     fn from(value: yhm::AccountError) -> Self {
         match value {
-            yhm::AccountError::InvalidState => ServiceError::InvalidAccountState,
+            yhm::AccountError::InvalidState | yhm::AccountError::TotpRequired => {
+                ServiceError::InvalidAccountState
+            }
             yhm::AccountError::Backend(e) => e.into(),
         }
     }
@@ -75,6 +80,29 @@ impl From<yhm::ObserverError> for ServiceError {
                 email: a.email().to_string(),
             },
             yhm::ObserverError::NoSuchAccount(e) => ServiceError::AccountNotFound { email: e },
+            yhm::ObserverError::AccountAlreadyExists(email) => {
+                ServiceError::AccountAlreadyActive { email }
+            }
+            yhm::ObserverError::ChangeBackend(e) => e.into(),
+        }
+    }
+}
+
+impl From<yhm::ChangeBackendError> for ServiceError {
+    fn from(value: yhm::ChangeBackendError) -> Self {
+        match value {
+            yhm::ChangeBackendError::BackendConfig {
+                account,
+                backend,
+                error,
+            } => ServiceError::Config {
+                error: ConfigError::BackendConfig {
+                    account,
+                    backend,
+                    error: error.to_string(),
+                },
+            },
+            yhm::ChangeBackendError::Account(e) => e.into(),
         }
     }
 }
@@ -23,6 +23,13 @@ impl Backend {
 
 pub type ObserverAccount = yhm::ObserverAccount;
 
+/// Result of logging out a single account with [Service::logout_all].
+pub struct LogoutResult {
+    pub email: String,
+    /// Reason the logout failed, if it did.
+    pub error: Option<String>,
+}
+
 pub struct Account {
     service: Arc<Service>,
     account: RwLock<yhm::Account>,
@@ -101,6 +108,14 @@ impl Service {
         Ok(accounts)
     }
 
+    pub fn get_accounts_needing_attention(&self) -> Result<Vec<ObserverAccount>, ServiceError> {
+        let accounts = self
+            .runtime
+            .block_on(async { self.observer.get_accounts_needing_attention().await })?;
+
+        Ok(accounts)
+    }
+
     pub fn add_account(&self, account: Arc<Account>) -> Result<(), ServiceError> {
         let account = {
             let mut accessor = account.account.write().unwrap();
@@ -118,18 +133,88 @@ impl Service {
         Ok(())
     }
 
+    pub fn logout_all(&self) -> Result<Vec<LogoutResult>, ServiceError> {
+        let results = self
+            .runtime
+            .block_on(async { self.observer.logout_all().await })?;
+
+        Ok(results
+            .into_iter()
+            .map(|(email, result)| LogoutResult {
+                email,
+                error: result.err().map(|e| ServiceError::from(e).to_string()),
+            })
+            .collect())
+    }
+
     pub fn remove_account(&self, email: String) -> Result<(), ServiceError> {
         self.runtime
             .block_on(async { self.observer.remove_account(email).await })?;
         Ok(())
     }
 
+    pub fn rename_account(&self, email: String, new_email: String) -> Result<(), ServiceError> {
+        self.runtime
+            .block_on(async { self.observer.rename_account(email, new_email).await })?;
+        Ok(())
+    }
+
+    pub fn change_backend(&self, email: String, backend: &Backend) -> Result<(), ServiceError> {
+        self.runtime
+            .block_on(async { self.observer.change_backend(email, backend.0.clone()).await })?;
+        Ok(())
+    }
+
+    pub fn resync_account(&self, email: String) -> Result<(), ServiceError> {
+        self.runtime
+            .block_on(async { self.observer.resync_account(email).await })?;
+        Ok(())
+    }
+
+    pub fn set_notify_folders(
+        &self,
+        email: String,
+        include: Vec<String>,
+        exclude: Vec<String>,
+    ) -> Result<(), ServiceError> {
+        self.runtime.block_on(async {
+            self.observer
+                .set_notify_folders(email, include, exclude)
+                .await
+        })?;
+        Ok(())
+    }
+
+    pub fn set_notify_cooldown(
+        &self,
+        email: String,
+        seconds: Option<u64>,
+    ) -> Result<(), ServiceError> {
+        self.runtime.block_on(async {
+            self.observer
+                .set_notify_cooldown(email, seconds.map(Duration::from_secs))
+                .await
+        })?;
+        Ok(())
+    }
+
+    pub fn verify_credentials(&self, email: String) -> Result<bool, ServiceError> {
+        let valid = self
+            .runtime
+            .block_on(async { self.observer.verify_credentials(email).await })?;
+        Ok(valid)
+    }
+
     pub fn pause(&self) -> Result<(), ServiceError> {
         self.runtime
             .block_on(async { self.observer.pause().await })?;
         Ok(())
     }
 
+    pub fn cancel_poll(&self) {
+        self.observer.cancel_poll();
+    }
+
     pub fn resume(&self) -> Result<(), ServiceError> {
         self.runtime
             .block_on(async { self.observer.resume().await })?;
@@ -152,7 +237,7 @@ impl Service {
 pub fn new_service(notifier: Box<dyn Notifier>) -> Result<Arc<Service>, ServiceError> {
     #[cfg(target_os = "android")]
     init_android_logger();
-    new_service_with_backends(notifier, get_backends()).map(Arc::new)
+    new_service_with_backends(notifier, get_backends(), Vec::new()).map(Arc::new)
 }
 
 pub fn new_service_from_config(
@@ -167,10 +252,22 @@ pub fn new_service_from_config(
 
     let config_backends = backends.iter().map(|x| x.0.clone()).collect::<Vec<_>>();
 
-    let accounts =
-        yhm::Config::load(&config_backends, bytes.as_bytes()).map_err(ConfigError::from)?;
+    let (accounts, errors) =
+        yhm::Config::load_partial(&config_backends, bytes.as_bytes()).map_err(ConfigError::from)?;
+
+    // Accounts which failed to load are kept in the config so they are not lost when it is
+    // stored again.
+    let mut unloaded_accounts = Vec::with_capacity(errors.len());
+    for (unloaded, e) in errors {
+        error!("Failed to load account from config: {e}");
+        from_config_cb.notify_error(
+            unloaded.email().to_string(),
+            ServiceError::Config { error: e.into() },
+        );
+        unloaded_accounts.push(unloaded);
+    }
 
-    let service = new_service_with_backends(notifier, backends)?;
+    let service = new_service_with_backends(notifier, backends, unloaded_accounts)?;
 
     debug!("Found {} account(s) in config file", accounts.len());
 
@@ -216,15 +313,18 @@ fn get_backends() -> Vec<Arc<Backend>> {
                 password: "foo".to_string(),
                 totp: None,
                 wait_time: Some(Duration::from_secs(2)),
+                ..Default::default()
             },
             yhm::backend::null::NullTestAccount {
                 email: "bar".to_string(),
                 password: "bar".to_string(),
                 totp: Some("1234".to_string()),
                 wait_time: Some(Duration::from_secs(2)),
+                ..Default::default()
             },
         ]),
         yhm::backend::proton::new_backend("web-mail@5.0.17.9"),
+        yhm::backend::proton::new_backend_version_other(),
     ]
     .into_iter()
     .map(|x| Arc::new(Backend(x)))
@@ -234,6 +334,7 @@ fn get_backends() -> Vec<Arc<Backend>> {
 fn new_service_with_backends(
     notifier: Box<dyn Notifier>,
     backends: Vec<Arc<Backend>>,
+    unloaded_accounts: Vec<yhm::UnloadedAccount>,
 ) -> Result<Service, ServiceError> {
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
@@ -243,7 +344,9 @@ fn new_service_with_backends(
             msg: format!("Failed to start tokio runtime {e}"),
         })?;
 
-    let (observer, task) = yhm::ObserverBuilder::new(Box::new(NotifierWrapper(notifier))).build();
+    let (observer, task) = yhm::ObserverBuilder::new(Box::new(NotifierWrapper(notifier)))
+        .unloaded_accounts(unloaded_accounts)
+        .build();
     let join_handle = runtime.spawn(task);
 
     Ok(Service {